@@ -1,10 +1,20 @@
-#[derive(Debug, Clone, Copy)]
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Size {
+    #[cfg_attr(feature = "serde", serde(with = "float"))]
     pub width: f64,
+    #[cfg_attr(feature = "serde", serde(with = "float"))]
     pub height: f64,
 }
 
 impl Size {
+    pub fn new(width: f64, height: f64) -> Size {
+        Size { width, height }
+    }
+
     pub fn zero() -> Size {
         Size {
             width: 0.0,
@@ -13,41 +23,425 @@ impl Size {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+/// The range of sizes a parent accepts from a child. Build it with the
+/// constructors, which keep `0 <= min <= max` on both axes.
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Constraint {
-    pub min_width: f64,
-    pub max_width: f64,
-    pub min_height: f64,
-    pub max_height: f64,
+    min_width: f64,
+    max_width: f64,
+    min_height: f64,
+    max_height: f64,
 }
 
 impl Constraint {
+    /// Any size up to `width` x `height`.
     pub fn new(width: f64, height: f64) -> Self {
+        Constraint::loose(Size::new(width, height))
+    }
+
+    /// Exactly `size`.
+    pub fn tight(size: Size) -> Self {
+        Constraint::between(size, size)
+    }
+
+    /// Any size up to `size`.
+    pub fn loose(size: Size) -> Self {
+        Constraint::between(Size::zero(), size)
+    }
+
+    /// Any size at all.
+    pub fn unbounded() -> Self {
+        Constraint::loose(Size::new(f64::INFINITY, f64::INFINITY))
+    }
+
+    /// Sizes between `min` and `max`. A `max` below `min` is raised to it.
+    pub fn between(min: Size, max: Size) -> Self {
+        let min_width = min.width.max(0.0);
+        let min_height = min.height.max(0.0);
         Constraint {
-            min_width: 0.0,
-            max_width: width,
-            min_height: 0.0,
-            max_height: height,
+            min_width,
+            max_width: max.width.max(min_width),
+            min_height,
+            max_height: max.height.max(min_height),
         }
     }
 
+    pub fn min_width(&self) -> f64 {
+        self.min_width
+    }
+
+    pub fn max_width(&self) -> f64 {
+        self.max_width
+    }
+
+    pub fn min_height(&self) -> f64 {
+        self.min_height
+    }
+
+    pub fn max_height(&self) -> f64 {
+        self.max_height
+    }
+
+    pub fn min_size(&self) -> Size {
+        Size::new(self.min_width, self.min_height)
+    }
+
+    pub fn max_size(&self) -> Size {
+        Size::new(self.max_width, self.max_height)
+    }
+
+    pub fn is_tight(&self) -> bool {
+        self.min_width == self.max_width && self.min_height == self.max_height
+    }
+
+    /// The same maximum without a minimum.
+    pub fn loosen(&self) -> Self {
+        Constraint::loose(self.max_size())
+    }
+
+    /// The size closest to `size` that satisfies the constraint. A NaN
+    /// dimension becomes the minimum.
+    pub fn constrain(&self, size: Size) -> Size {
+        Size {
+            width: size.width.max(self.min_width).min(self.max_width),
+            height: size.height.max(self.min_height).min(self.max_height),
+        }
+    }
+
+    /// The constraint left for a child once `insets` are taken away.
+    pub fn deflate(&self, insets: EdgeInsets) -> Self {
+        let (dx, dy) = (insets.horizontal(), insets.vertical());
+        Constraint::between(
+            Size::new(self.min_width - dx, self.min_height - dy),
+            Size::new(
+                (self.max_width - dx).max(0.0),
+                (self.max_height - dy).max(0.0),
+            ),
+        )
+    }
+
     pub fn sub_width(&mut self, width: f64) {
-        self.max_width = (self.max_width - width).clamp(self.min_width, f64::INFINITY)
+        self.max_width = (self.max_width - width).max(self.min_width)
     }
 
-    pub fn sub_height(&mut self, width: f64) {
-        self.max_height = (self.max_width - width).clamp(self.min_height, f64::INFINITY)
+    pub fn sub_height(&mut self, height: f64) {
+        self.max_height = (self.max_height - height).max(self.min_height)
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Offset {
+    #[cfg_attr(feature = "serde", serde(with = "float"))]
     pub x: f64,
+    #[cfg_attr(feature = "serde", serde(with = "float"))]
     pub y: f64,
 }
 
 impl Offset {
+    pub fn new(x: f64, y: f64) -> Self {
+        Offset { x, y }
+    }
+
     pub fn zero() -> Self {
         Self { x: 0.0, y: 0.0 }
     }
+
+    pub fn translate(&self, dx: f64, dy: f64) -> Self {
+        Offset {
+            x: self.x + dx,
+            y: self.y + dy,
+        }
+    }
+}
+
+/// An area of the screen. The left and top edges are inside the rectangle,
+/// the right and bottom edges are not.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Rect {
+    pub origin: Offset,
+    pub size: Size,
+}
+
+impl Rect {
+    pub fn new(origin: Offset, size: Size) -> Self {
+        Rect { origin, size }
+    }
+
+    pub fn left(&self) -> f64 {
+        self.origin.x
+    }
+
+    pub fn top(&self) -> f64 {
+        self.origin.y
+    }
+
+    pub fn right(&self) -> f64 {
+        self.origin.x + self.size.width
+    }
+
+    pub fn bottom(&self) -> f64 {
+        self.origin.y + self.size.height
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size.width <= 0.0 || self.size.height <= 0.0
+    }
+
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        x >= self.left() && x < self.right() && y >= self.top() && y < self.bottom()
+    }
+
+    /// The overlap of both rectangles, if they overlap.
+    pub fn intersect(&self, other: &Rect) -> Option<Rect> {
+        let left = self.left().max(other.left());
+        let top = self.top().max(other.top());
+        let right = self.right().min(other.right());
+        let bottom = self.bottom().min(other.bottom());
+        if left < right && top < bottom {
+            Some(Rect::new(
+                Offset::new(left, top),
+                Size::new(right - left, bottom - top),
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// The smallest rectangle containing both.
+    pub fn union(&self, other: &Rect) -> Rect {
+        if self.is_empty() {
+            return *other;
+        }
+        if other.is_empty() {
+            return *self;
+        }
+        let left = self.left().min(other.left());
+        let top = self.top().min(other.top());
+        Rect::new(
+            Offset::new(left, top),
+            Size::new(
+                self.right().max(other.right()) - left,
+                self.bottom().max(other.bottom()) - top,
+            ),
+        )
+    }
+
+    /// The rectangle shrunk by `insets`, never to less than zero size.
+    pub fn inset(&self, insets: EdgeInsets) -> Rect {
+        Rect::new(
+            self.origin.translate(insets.left, insets.top),
+            Size::new(
+                (self.size.width - insets.horizontal()).max(0.0),
+                (self.size.height - insets.vertical()).max(0.0),
+            ),
+        )
+    }
+}
+
+/// Distances from each side of a rectangle, e.g. for padding.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct EdgeInsets {
+    pub top: f64,
+    pub right: f64,
+    pub bottom: f64,
+    pub left: f64,
+}
+
+impl EdgeInsets {
+    pub fn new(top: f64, right: f64, bottom: f64, left: f64) -> Self {
+        EdgeInsets {
+            top,
+            right,
+            bottom,
+            left,
+        }
+    }
+
+    pub fn zero() -> Self {
+        EdgeInsets::all(0.0)
+    }
+
+    pub fn all(value: f64) -> Self {
+        EdgeInsets::new(value, value, value, value)
+    }
+
+    pub fn symmetric(vertical: f64, horizontal: f64) -> Self {
+        EdgeInsets::new(vertical, horizontal, vertical, horizontal)
+    }
+
+    /// Left plus right.
+    pub fn horizontal(&self) -> f64 {
+        self.left + self.right
+    }
+
+    /// Top plus bottom.
+    pub fn vertical(&self) -> f64 {
+        self.top + self.bottom
+    }
+
+    /// `size` grown by the insets.
+    pub fn inflate(&self, size: Size) -> Size {
+        Size::new(
+            size.width + self.horizontal(),
+            size.height + self.vertical(),
+        )
+    }
+}
+
+impl From<f64> for EdgeInsets {
+    fn from(value: f64) -> Self {
+        EdgeInsets::all(value)
+    }
+}
+
+/// Where to put something smaller than its container, as fractions of the
+/// free space: 0 is the left or top edge, 1 the right or bottom edge.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Alignment {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Default for Alignment {
+    fn default() -> Self {
+        Alignment::CENTER
+    }
+}
+
+impl Alignment {
+    pub const TOP_LEADING: Alignment = Alignment::new(0.0, 0.0);
+    pub const TOP: Alignment = Alignment::new(0.5, 0.0);
+    pub const TOP_TRAILING: Alignment = Alignment::new(1.0, 0.0);
+    pub const LEADING: Alignment = Alignment::new(0.0, 0.5);
+    pub const CENTER: Alignment = Alignment::new(0.5, 0.5);
+    pub const TRAILING: Alignment = Alignment::new(1.0, 0.5);
+    pub const BOTTOM_LEADING: Alignment = Alignment::new(0.0, 1.0);
+    pub const BOTTOM: Alignment = Alignment::new(0.5, 1.0);
+    pub const BOTTOM_TRAILING: Alignment = Alignment::new(1.0, 1.0);
+
+    pub const fn new(x: f64, y: f64) -> Self {
+        Alignment { x, y }
+    }
+
+    /// The offset of `child` inside `container`, rounded down to whole cells.
+    pub fn position(&self, child: Size, container: Size) -> Offset {
+        Offset {
+            x: ((container.width - child.width) * self.x).floor(),
+            y: ((container.height - child.height) * self.y).floor(),
+        }
+    }
+}
+
+/// Floats as numbers, or as `"inf"`, `"-inf"` and `"NaN"`, which numbers in
+/// formats like JSON can't hold. Unbounded sizes are infinite.
+#[cfg(feature = "serde")]
+mod float {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+        match *value {
+            value if value.is_finite() => serializer.serialize_f64(value),
+            value if value.is_nan() => serializer.serialize_str("NaN"),
+            f64::INFINITY => serializer.serialize_str("inf"),
+            _ => serializer.serialize_str("-inf"),
+        }
+    }
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Float {
+        Number(f64),
+        Name(String),
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+        match Float::deserialize(deserializer)? {
+            Float::Number(value) => Ok(value),
+            Float::Name(name) => match name.as_str() {
+                "inf" => Ok(f64::INFINITY),
+                "-inf" => Ok(f64::NEG_INFINITY),
+                "NaN" => Ok(f64::NAN),
+                _ => Err(D::Error::custom(format!("not a float: {:?}", name))),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sub_height_takes_from_the_height() {
+        let mut constraint = Constraint::new(80.0, 24.0);
+        constraint.sub_height(4.0);
+        assert_eq!(constraint.max_size(), Size::new(80.0, 20.0));
+        constraint.sub_height(30.0);
+        assert_eq!(constraint.max_size(), Size::new(80.0, 0.0));
+    }
+
+    #[test]
+    fn sub_width_takes_from_the_width() {
+        let mut constraint = Constraint::new(80.0, 24.0);
+        constraint.sub_width(10.0);
+        assert_eq!(constraint.max_size(), Size::new(70.0, 24.0));
+    }
+
+    #[test]
+    fn tight_accepts_one_size() {
+        let constraint = Constraint::tight(Size::new(10.0, 5.0));
+        assert!(constraint.is_tight());
+        assert_eq!(constraint.min_size(), Size::new(10.0, 5.0));
+        assert_eq!(
+            constraint.constrain(Size::new(3.0, 8.0)),
+            Size::new(10.0, 5.0)
+        );
+    }
+
+    #[test]
+    fn loose_starts_at_zero() {
+        let constraint = Constraint::loose(Size::new(10.0, 5.0));
+        assert_eq!(constraint.min_size(), Size::zero());
+        assert_eq!(constraint.max_size(), Size::new(10.0, 5.0));
+        assert_eq!(
+            Constraint::loose(Size::new(-1.0, f64::NAN)).max_size(),
+            Size::zero()
+        );
+    }
+
+    #[test]
+    fn between_keeps_max_above_min() {
+        let constraint = Constraint::between(Size::new(8.0, -2.0), Size::new(4.0, 6.0));
+        assert_eq!(constraint.min_size(), Size::new(8.0, 0.0));
+        assert_eq!(constraint.max_size(), Size::new(8.0, 6.0));
+    }
+
+    #[test]
+    fn deflate_takes_the_insets_from_both_bounds() {
+        let constraint = Constraint::between(Size::new(4.0, 4.0), Size::new(20.0, 10.0));
+        let inner = constraint.deflate(EdgeInsets::symmetric(1.0, 3.0));
+        assert_eq!(inner.min_size(), Size::new(0.0, 2.0));
+        assert_eq!(inner.max_size(), Size::new(14.0, 8.0));
+        let inner = constraint.deflate(EdgeInsets::all(20.0));
+        assert_eq!(inner.max_size(), Size::zero());
+    }
+
+    #[test]
+    fn constrain_clamps_to_the_bounds() {
+        let constraint = Constraint::between(Size::new(2.0, 2.0), Size::new(10.0, 5.0));
+        assert_eq!(
+            constraint.constrain(Size::new(1.0, 7.0)),
+            Size::new(2.0, 5.0)
+        );
+        assert_eq!(
+            constraint.constrain(Size::new(f64::NAN, f64::INFINITY)),
+            Size::new(2.0, 5.0)
+        );
+        assert_eq!(
+            Constraint::unbounded().constrain(Size::new(3.0, 4.0)),
+            Size::new(3.0, 4.0)
+        );
+    }
 }
@@ -1,17 +1,40 @@
 extern crate proc_macro;
 
 use proc_macro::TokenStream;
-use quote::quote;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote, ToTokens};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
 use syn::*;
 
-#[proc_macro_derive(Hydrate)]
+/// Fields typed `State<_>` are state: they keep their value across
+/// hydrations and make the view dirty. Other fields are props, copied from
+/// the new view and compared by `is_same`. `states` lists the state fields
+/// whose values are `Debug`; in generic views, that is known only if the
+/// type parameter is declared `Debug + 'static`.
+///
+/// Field attributes:
+/// - `#[hydrate(state)]`: a state field whose type is not spelled `State`,
+///   e.g. an alias.
+/// - `#[hydrate(prop)]`: a prop whose type is spelled `State` but isn't
+///   quill's.
+/// - `#[hydrate(skip)]`: keep the field as it is, and leave it out of
+///   `is_same`.
+/// - `#[hydrate(ignore_eq)]`: leave the field out of `is_same`, e.g. for
+///   callbacks. It is still copied by `refresh` when the view is otherwise
+///   the same.
+/// - `#[hydrate(with = path)]`: merge the field with
+///   `path(&mut self.field, &other.field)` instead of cloning it.
+#[proc_macro_derive(Hydrate, attributes(hydrate))]
 pub fn hydrate_derive(input: TokenStream) -> TokenStream {
     // Construct a representation of Rust code as a syntax tree
     // that we can manipulate
-    let ast = syn::parse(input).unwrap();
+    let ast = parse_macro_input!(input as DeriveInput);
 
     // Build the trait implementation
     hydrate_macro(ast)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
 }
 
 fn is_state_type(ty: &Type) -> bool {
@@ -19,7 +42,7 @@ fn is_state_type(ty: &Type) -> bool {
         Type::Path(TypePath {
             path: Path { segments, .. },
             ..
-        }) => match segments.first() {
+        }) => match segments.last() {
             Some(segment) => segment.ident == "State",
             None => false,
         },
@@ -27,64 +50,422 @@ fn is_state_type(ty: &Type) -> bool {
     }
 }
 
-fn hydrate_macro(ast: syn::DeriveInput) -> TokenStream {
-    let name = ast.ident;
-    let non_state_fields = match &ast.data {
-        Data::Struct(s) => match &s.fields {
-            Fields::Named(FieldsNamed { named, .. }) => named
-                .iter()
-                .filter_map(|f| {
-                    if is_state_type(&f.ty) {
-                        None
-                    } else {
-                        Some(&f.ident)
+/// One argument of `#[hydrate(...)]`.
+enum HydrateArg {
+    State,
+    Prop,
+    Skip,
+    IgnoreEq,
+    With(Ident, Path),
+}
+
+impl Parse for HydrateArg {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let ident: Ident = input.parse()?;
+        match ident.to_string().as_str() {
+            "state" => Ok(HydrateArg::State),
+            "prop" => Ok(HydrateArg::Prop),
+            "skip" => Ok(HydrateArg::Skip),
+            "ignore_eq" => Ok(HydrateArg::IgnoreEq),
+            "with" => {
+                input.parse::<Token![=]>()?;
+                Ok(HydrateArg::With(ident, input.parse()?))
+            }
+            _ => Err(Error::new(
+                ident.span(),
+                "expected `state`, `prop`, `skip`, `ignore_eq` or `with = path`",
+            )),
+        }
+    }
+}
+
+#[derive(PartialEq)]
+enum FieldKind {
+    Prop,
+    State,
+    Skip,
+}
+
+/// A field of a struct or enum variant.
+struct FieldInfo<'a> {
+    /// `name` or `0`, for `self.member` and `Variant { member: binding }`.
+    member: Member,
+    /// The name shown by `Hydrate::states`.
+    name: String,
+    ty: &'a Type,
+    kind: FieldKind,
+    /// Whether `is_same` compares the field.
+    compared: bool,
+    /// Merges the field instead of `clone_from`.
+    with: Option<Path>,
+}
+
+impl FieldInfo<'_> {
+    fn is_prop(&self) -> bool {
+        self.kind == FieldKind::Prop
+    }
+
+    fn is_state(&self) -> bool {
+        self.kind == FieldKind::State
+    }
+
+    /// Copy or merge the field from `theirs` into `mine`, both references.
+    fn hydrate(&self, mine: TokenStream2, theirs: TokenStream2) -> TokenStream2 {
+        match &self.with {
+            Some(with) => quote!(#with(#mine, #theirs);),
+            None => quote!(::std::clone::Clone::clone_from(#mine, #theirs);),
+        }
+    }
+}
+
+fn field_infos(fields: &Fields) -> Result<Vec<FieldInfo<'_>>> {
+    let mut infos = Vec::new();
+    for (nth, field) in fields.iter().enumerate() {
+        let (member, name) = match &field.ident {
+            Some(ident) => (Member::Named(ident.clone()), ident.to_string()),
+            None => (
+                Member::Unnamed(Index {
+                    index: nth as u32,
+                    span: Span::call_site(),
+                }),
+                nth.to_string(),
+            ),
+        };
+        let mut info = FieldInfo {
+            member,
+            name,
+            ty: &field.ty,
+            kind: if is_state_type(&field.ty) {
+                FieldKind::State
+            } else {
+                FieldKind::Prop
+            },
+            compared: true,
+            with: None,
+        };
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path.is_ident("hydrate"))
+        {
+            let args =
+                attr.parse_args_with(Punctuated::<HydrateArg, Token![,]>::parse_terminated)?;
+            for arg in args {
+                match arg {
+                    HydrateArg::State => info.kind = FieldKind::State,
+                    HydrateArg::Prop => info.kind = FieldKind::Prop,
+                    HydrateArg::Skip => info.kind = FieldKind::Skip,
+                    HydrateArg::IgnoreEq => info.compared = false,
+                    HydrateArg::With(ident, path) => {
+                        if info.with.is_some() {
+                            return Err(Error::new(ident.span(), "`with` is given twice"));
+                        }
+                        info.with = Some(path);
                     }
-                })
-                .collect(),
-            Fields::Unit => vec![],
-            _ => panic!(),
-        },
-        _ => panic!("expected a struct"),
+                }
+            }
+        }
+        if info.with.is_some() && !info.is_prop() {
+            return Err(Error::new_spanned(
+                field,
+                "`with` cannot be used on state or skipped fields",
+            ));
+        }
+        infos.push(info);
+    }
+    Ok(infos)
+}
+
+fn hydrate_macro(ast: DeriveInput) -> Result<TokenStream2> {
+    let name = &ast.ident;
+    let (body, fields) = match &ast.data {
+        Data::Struct(data) => {
+            let fields = field_infos(&data.fields)?;
+            (struct_methods(&fields), fields)
+        }
+        Data::Enum(data) => {
+            let mut variants = Vec::new();
+            for variant in &data.variants {
+                variants.push((&variant.ident, field_infos(&variant.fields)?));
+            }
+            let body = enum_methods(name, &variants);
+            (
+                body,
+                variants
+                    .into_iter()
+                    .flat_map(|(_, fields)| fields)
+                    .collect(),
+            )
+        }
+        Data::Union(data) => {
+            return Err(Error::new(
+                data.union_token.span,
+                "Hydrate can only be derived for structs and enums",
+            ))
+        }
     };
-    let state_fields = match &ast.data {
-        Data::Struct(s) => match &s.fields {
-            Fields::Named(FieldsNamed { named, .. }) => {
-                named
-                    .iter()
-                    .filter_map(|f| {
-                        // panic!("Type: {:?}", f.ty);
-                        if is_state_type(&f.ty) {
-                            Some(&f.ident)
-                        } else {
-                            None
-                        }
-                    })
-                    .collect()
+
+    // Generic views need their fields to be hydratable, whatever the type
+    // arguments.
+    let mut generics = ast.generics.clone();
+    if ast.generics.type_params().next().is_some() {
+        let (_, ty_generics, _) = ast.generics.split_for_impl();
+        let where_clause = generics.make_where_clause();
+        for field in &fields {
+            let ty = field.ty;
+            if field.is_prop() && field.with.is_none() {
+                where_clause.predicates.push(parse_quote!(#ty: Clone));
             }
-            Fields::Unit => vec![],
-            _ => panic!(),
-        },
-        _ => panic!("expected a struct"),
+            if field.is_prop() && field.compared {
+                where_clause.predicates.push(parse_quote!(#ty: PartialEq));
+            }
+        }
+        if let Data::Enum(_) = ast.data {
+            where_clause
+                .predicates
+                .push(parse_quote!(#name #ty_generics: Clone));
+        }
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics Hydrate for #name #ty_generics #where_clause {
+            #body
+        }
+    })
+}
+
+fn struct_methods(fields: &[FieldInfo]) -> TokenStream2 {
+    let hydrate = fields.iter().filter(|f| f.is_prop()).map(|f| {
+        let member = &f.member;
+        f.hydrate(quote!(&mut self.#member), quote!(&other.#member))
+    });
+    let ignored: Vec<_> = fields
+        .iter()
+        .filter(|f| f.is_prop() && !f.compared)
+        .map(|f| {
+            let member = &f.member;
+            f.hydrate(quote!(&mut self.#member), quote!(&other.#member))
+        })
+        .collect();
+    let refresh = if ignored.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            fn refresh(&mut self, other: &Self) {
+                #(#ignored)*
+            }
+        }
     };
-    let is_dirty_method = if state_fields.is_empty() {
+    let compared: Vec<_> = fields
+        .iter()
+        .filter(|f| f.is_prop() && f.compared)
+        .map(|f| &f.member)
+        .collect();
+    let state_fields: Vec<_> = fields
+        .iter()
+        .filter(|f| f.is_state())
+        .map(|f| &f.member)
+        .collect();
+    let state_names: Vec<_> = fields
+        .iter()
+        .filter(|f| f.is_state())
+        .map(|f| &f.name)
+        .collect();
+    let states: Vec<_> = state_fields.iter().map(|f| quote!(&self.#f)).collect();
+    let inspect = inspect_states(&state_names, &states);
+    let state_methods = if state_fields.is_empty() {
         quote! {}
     } else {
         quote! {
             fn is_dirty(&self) -> bool {
                 #(self.#state_fields.is_dirty()) || *
             }
+            fn states(&self) -> Vec<(&'static str, &dyn ::quill::InspectState)> {
+                #inspect
+            }
         }
     };
-    let gen = quote! {
-        impl Hydrate for #name {
-            fn hydrate(&mut self, other: &Self) {
-                #(self.#non_state_fields.clone_from(&other.#non_state_fields);) *
+    quote! {
+        fn hydrate(&mut self, other: &Self) {
+            #(#hydrate)*
+        }
+        fn is_same(&self, other: &Self) -> bool {
+            true #(&& self.#compared == other.#compared)*
+        }
+        #refresh
+        #state_methods
+    }
+}
+
+/// Lists the `states`, references to `State` fields, that can be inspected.
+fn inspect_states(names: &[&String], states: &[impl ToTokens]) -> TokenStream2 {
+    quote! {
+        #[allow(unused_imports)]
+        use ::quill::__private::{Inspect, InspectDebug as _, InspectOther as _};
+        let mut states: Vec<(&'static str, &dyn ::quill::InspectState)> = Vec::new();
+        #(
+            if let Some(state) = (&Inspect(#states)).inspect() {
+                states.push((#names, state));
+            }
+        )*
+        states
+    }
+}
+
+/// Same variants are hydrated field by field, a different variant replaces
+/// the view.
+fn enum_methods(name: &Ident, variants: &[(&Ident, Vec<FieldInfo>)]) -> TokenStream2 {
+    let mut hydrate_arms = Vec::new();
+    let mut refresh_arms = Vec::new();
+    let mut same_arms = Vec::new();
+    let mut dirty_arms = Vec::new();
+    let mut states_arms = Vec::new();
+    for (ident, fields) in variants {
+        // Binds the fields picked by `pick` of both sides, as `mine_n` and
+        // `theirs_n`.
+        let patterns = |pick: &dyn Fn(&FieldInfo) -> bool| {
+            let picked: Vec<_> = fields.iter().enumerate().filter(|(_, f)| pick(f)).collect();
+            let members: Vec<_> = picked.iter().map(|(_, f)| &f.member).collect();
+            let mine: Vec<_> = picked
+                .iter()
+                .map(|(nth, _)| format_ident!("mine_{}", nth))
+                .collect();
+            let theirs: Vec<_> = picked
+                .iter()
+                .map(|(nth, _)| format_ident!("theirs_{}", nth))
+                .collect();
+            (
+                quote!(#name::#ident { #(#members: #mine,)* .. }),
+                quote!(#name::#ident { #(#members: #theirs,)* .. }),
+            )
+        };
+
+        let (mine, theirs) = patterns(&|f| f.is_prop());
+        let copies = fields
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| f.is_prop())
+            .map(|(nth, f)| {
+                let (mine, theirs) = (
+                    format_ident!("mine_{}", nth),
+                    format_ident!("theirs_{}", nth),
+                );
+                f.hydrate(quote!(#mine), quote!(#theirs))
+            });
+        hydrate_arms.push(quote! {
+            (#mine, #theirs) => {
+                #(#copies)*
+            }
+        });
+
+        let (mine, theirs) = patterns(&|f| f.is_prop() && !f.compared);
+        let copies: Vec<_> = fields
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| f.is_prop() && !f.compared)
+            .map(|(nth, f)| {
+                let (mine, theirs) = (
+                    format_ident!("mine_{}", nth),
+                    format_ident!("theirs_{}", nth),
+                );
+                f.hydrate(quote!(#mine), quote!(#theirs))
+            })
+            .collect();
+        if !copies.is_empty() {
+            refresh_arms.push(quote! {
+                (#mine, #theirs) => {
+                    #(#copies)*
+                }
+            });
+        }
+
+        let (mine, theirs) = patterns(&|f| f.is_prop() && f.compared);
+        let compared = fields
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| f.is_prop() && f.compared)
+            .map(|(nth, _)| {
+                let (mine, theirs) = (
+                    format_ident!("mine_{}", nth),
+                    format_ident!("theirs_{}", nth),
+                );
+                quote!(&& #mine == #theirs)
+            });
+        same_arms.push(quote! {
+            (#mine, #theirs) => true #(#compared)*,
+        });
+
+        let (pattern, _) = patterns(&|f| f.is_state());
+        let states: Vec<_> = fields
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| f.is_state())
+            .map(|(nth, _)| format_ident!("mine_{}", nth))
+            .collect();
+        let state_names: Vec<_> = fields
+            .iter()
+            .filter(|f| f.is_state())
+            .map(|f| &f.name)
+            .collect();
+        dirty_arms.push(if states.is_empty() {
+            quote!(#pattern => true,)
+        } else {
+            quote!(#pattern => #(#states.is_dirty()) || *,)
+        });
+        let inspect = inspect_states(&state_names, &states);
+        states_arms.push(quote! {
+            #pattern => { #inspect }
+        });
+    }
+    let has_states = variants
+        .iter()
+        .flat_map(|(_, fields)| fields)
+        .any(|f| f.is_state());
+    let state_methods = if has_states {
+        quote! {
+            fn is_dirty(&self) -> bool {
+                match self {
+                    #(#dirty_arms)*
+                }
+            }
+            fn states(&self) -> Vec<(&'static str, &dyn ::quill::InspectState)> {
+                match self {
+                    #(#states_arms)*
+                }
             }
-            fn is_same(&self, other: &Self) -> bool {
-                self.eq(&other)
+        }
+    } else {
+        quote! {}
+    };
+    let refresh = if refresh_arms.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            #[allow(unreachable_patterns)]
+            fn refresh(&mut self, other: &Self) {
+                match (self, other) {
+                    #(#refresh_arms)*
+                    _ => {}
+                }
             }
-            #is_dirty_method
         }
     };
-    gen.into()
+    quote! {
+        #[allow(unreachable_patterns)]
+        fn hydrate(&mut self, other: &Self) {
+            match (self, other) {
+                #(#hydrate_arms)*
+                (this, other) => *this = other.clone(),
+            }
+        }
+        #[allow(unreachable_patterns)]
+        fn is_same(&self, other: &Self) -> bool {
+            match (self, other) {
+                #(#same_arms)*
+                _ => false,
+            }
+        }
+        #refresh
+        #state_methods
+    }
 }
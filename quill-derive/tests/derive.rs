@@ -0,0 +1,294 @@
+use quill::*;
+use quill_derive::Hydrate;
+use std::fmt::Debug;
+
+#[derive(Debug, Clone, PartialEq, Hydrate)]
+struct Label(String, State<u32>);
+
+#[test]
+fn tuple_struct() {
+    let mut label = Label("a".to_string(), State::new(1));
+    let state = label.1.clone();
+    assert!(label.is_same(&Label("a".to_string(), State::new(2))));
+    assert!(!label.is_same(&Label("b".to_string(), State::new(1))));
+
+    label.hydrate(&Label("b".to_string(), State::new(2)));
+    assert_eq!(label.0, "b");
+    // The state is kept.
+    assert_eq!(label.1.get(), 1);
+    assert!(label.1 == state);
+
+    assert!(label.is_dirty());
+    label.1.clean();
+    assert!(!label.is_dirty());
+    let states = label.states();
+    assert_eq!(states.len(), 1);
+    assert_eq!(states[0].0, "1");
+}
+
+#[derive(Debug, Clone, PartialEq, Hydrate)]
+enum Shape {
+    Circle { radius: u32, hovered: State<bool> },
+    Square(u32),
+    Empty,
+}
+
+#[test]
+fn enum_same_variant() {
+    let hovered = State::new(true);
+    let mut shape = Shape::Circle {
+        radius: 1,
+        hovered: hovered.clone(),
+    };
+    let bigger = Shape::Circle {
+        radius: 2,
+        hovered: State::new(false),
+    };
+    assert!(!shape.is_same(&bigger));
+    assert!(shape.is_same(&Shape::Circle {
+        radius: 1,
+        hovered: State::new(false),
+    }));
+
+    shape.hydrate(&bigger);
+    match &shape {
+        Shape::Circle { radius, hovered: h } => {
+            assert_eq!(*radius, 2);
+            // The state is kept.
+            assert!(*h == hovered);
+            assert!(h.get());
+        }
+        _ => panic!("expected a circle, got {:?}", shape),
+    }
+    assert_eq!(shape.states().len(), 1);
+    hovered.clean();
+    assert!(!shape.is_dirty());
+}
+
+#[test]
+fn enum_different_variant() {
+    let mut shape = Shape::Circle {
+        radius: 1,
+        hovered: State::new(false),
+    };
+    assert!(!shape.is_same(&Shape::Square(1)));
+    assert!(!Shape::Empty.is_same(&Shape::Square(1)));
+    assert!(Shape::Empty.is_same(&Shape::Empty));
+
+    shape.hydrate(&Shape::Square(3));
+    assert_eq!(shape, Shape::Square(3));
+    // Variants without states are always dirty, like views without states.
+    assert!(shape.is_dirty());
+    assert!(shape.states().is_empty());
+}
+
+trait Item: Debug {}
+
+impl Item for u8 {}
+
+#[derive(Debug, Clone, PartialEq, Hydrate)]
+struct Row<T, U>
+where
+    T: Item,
+    U: Debug + 'static,
+{
+    item: T,
+    selected: State<U>,
+}
+
+#[test]
+fn generic_where_clause() {
+    let mut row = Row {
+        item: 1u8,
+        selected: State::new("no"),
+    };
+    assert!(row.is_same(&Row {
+        item: 1u8,
+        selected: State::new("yes"),
+    }));
+
+    row.hydrate(&Row {
+        item: 2u8,
+        selected: State::new("yes"),
+    });
+    assert_eq!(row.item, 2);
+    assert_eq!(row.selected.get(), "no");
+    assert_eq!(row.states()[0].1.debug(), "\"no\"");
+}
+
+#[derive(Clone, PartialEq)]
+struct NoDebug;
+
+#[derive(Clone, Hydrate)]
+struct Opaque {
+    hidden: State<NoDebug>,
+    shown: State<u8>,
+}
+
+#[derive(Clone, Hydrate)]
+struct OpaqueOf<T> {
+    item: State<T>,
+}
+
+#[test]
+fn states_without_debug() {
+    let opaque = Opaque {
+        hidden: State::new(NoDebug),
+        shown: State::new(1),
+    };
+    // Non-`Debug` states are still states, but are left out of `states`.
+    opaque.hidden.set(NoDebug);
+    assert!(opaque.is_dirty());
+    let states = opaque.states();
+    assert_eq!(states.len(), 1);
+    assert_eq!(states[0].0, "shown");
+
+    // Without a `Debug` bound, the derive can't tell.
+    let generic = OpaqueOf {
+        item: State::new(1u8),
+    };
+    assert!(generic.states().is_empty());
+}
+
+mod user {
+    /// Not quill's `State`.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct State(pub u8);
+}
+
+#[derive(Debug, Clone, PartialEq, Hydrate)]
+struct Machine {
+    #[hydrate(prop)]
+    state: user::State,
+}
+
+#[test]
+fn prop_named_state() {
+    let mut machine = Machine {
+        state: user::State(1),
+    };
+    assert!(!machine.is_same(&Machine {
+        state: user::State(2),
+    }));
+    machine.hydrate(&Machine {
+        state: user::State(2),
+    });
+    assert_eq!(machine.state, user::State(2));
+    assert!(machine.states().is_empty());
+}
+
+type Counter = State<u32>;
+
+fn append(mine: &mut Vec<u32>, theirs: &Vec<u32>) {
+    mine.extend(theirs);
+}
+
+#[derive(Debug, Clone, Hydrate)]
+struct Fields {
+    title: String,
+    width: u16,
+    #[hydrate(state)]
+    count: Counter,
+    #[hydrate(skip)]
+    cache: u32,
+    #[hydrate(ignore_eq)]
+    on_click: fn() -> u32,
+    #[hydrate(with = append)]
+    log: Vec<u32>,
+}
+
+fn fields(title: &str, on_click: fn() -> u32) -> Fields {
+    Fields {
+        title: title.to_string(),
+        width: 10,
+        count: State::new(0),
+        cache: 0,
+        on_click,
+        log: vec![1],
+    }
+}
+
+#[test]
+fn is_same_compares_props_field_by_field() {
+    let a = fields("a", || 1);
+    assert!(a.is_same(&fields("a", || 1)));
+    assert!(!a.is_same(&fields("b", || 1)));
+    assert!(!a.is_same(&Fields {
+        width: 11,
+        ..fields("a", || 1)
+    }));
+    assert!(!a.is_same(&Fields {
+        log: vec![2],
+        ..fields("a", || 1)
+    }));
+    // States, skipped and ignored fields are not compared.
+    assert!(a.is_same(&Fields {
+        cache: 7,
+        count: State::new(3),
+        ..fields("a", || 2)
+    }));
+}
+
+#[test]
+fn attributes() {
+    let mut a = fields("a", || 1);
+    let count = a.count.clone();
+    a.cache = 3;
+    a.hydrate(&Fields {
+        cache: 7,
+        ..fields("b", || 2)
+    });
+    assert_eq!(a.title, "b");
+    // `state` keeps the alias, and lists it.
+    assert!(a.count == count);
+    assert_eq!(a.states().len(), 1);
+    assert_eq!(a.states()[0].0, "count");
+    // `skip` keeps the field.
+    assert_eq!(a.cache, 3);
+    // `ignore_eq` copies the field.
+    assert_eq!((a.on_click)(), 2);
+    // `with` merges the field.
+    assert_eq!(a.log, [1, 1]);
+}
+
+#[test]
+fn refresh_copies_only_ignored_props() {
+    let mut a = fields("a", || 1);
+    a.refresh(&Fields {
+        cache: 7,
+        ..fields("b", || 2)
+    });
+    assert_eq!((a.on_click)(), 2);
+    assert_eq!(a.title, "a");
+    assert_eq!(a.cache, 0);
+    assert_eq!(a.log, [1]);
+}
+
+#[derive(Debug, Clone, Hydrate)]
+enum Action {
+    Click(#[hydrate(ignore_eq)] fn() -> u32, String),
+    None,
+}
+
+#[test]
+fn refresh_enum() {
+    let mut action = Action::Click(|| 1, "a".to_string());
+    assert!(action.is_same(&Action::Click(|| 2, "a".to_string())));
+    action.refresh(&Action::Click(|| 2, "b".to_string()));
+    match &action {
+        Action::Click(on_click, label) => {
+            assert_eq!(on_click(), 2);
+            assert_eq!(label, "a");
+        }
+        _ => panic!("expected a click, got {:?}", action),
+    }
+    // A different variant is left to `hydrate`.
+    action.refresh(&Action::None);
+    assert!(matches!(action, Action::Click(..)));
+}
+
+#[test]
+fn compile_errors() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,9 @@
+use quill_derive::Hydrate;
+
+#[derive(Hydrate)]
+union Bits {
+    int: u32,
+    float: f32,
+}
+
+fn main() {}
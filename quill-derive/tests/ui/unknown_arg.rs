@@ -0,0 +1,9 @@
+use quill_derive::Hydrate;
+
+#[derive(Clone, PartialEq, Hydrate)]
+struct Label {
+    #[hydrate(foo)]
+    text: String,
+}
+
+fn main() {}
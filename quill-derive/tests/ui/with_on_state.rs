@@ -0,0 +1,14 @@
+use quill::State;
+use quill_derive::Hydrate;
+
+fn merge(mine: &mut State<u32>, theirs: &State<u32>) {
+    mine.set(theirs.get());
+}
+
+#[derive(Clone, PartialEq, Hydrate)]
+struct Counter {
+    #[hydrate(with = merge)]
+    count: State<u32>,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use quill_derive::Hydrate;
+
+fn merge(mine: &mut String, theirs: &String) {
+    mine.push_str(theirs);
+}
+
+#[derive(Clone, PartialEq, Hydrate)]
+struct Label {
+    #[hydrate(with = merge, with = merge)]
+    text: String,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use quill_derive::Hydrate;
+
+#[derive(Clone, PartialEq, Hydrate)]
+struct Label {
+    #[hydrate(with)]
+    text: String,
+}
+
+fn main() {}
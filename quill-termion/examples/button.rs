@@ -0,0 +1,39 @@
+use quill::*;
+use quill_derive::*;
+use quill_termion::*;
+
+#[derive(Debug, Clone, PartialEq, Hydrate)]
+struct App {
+    pub count: State<u32>,
+}
+
+impl App {
+    fn new() -> Self {
+        App {
+            count: State::new(0),
+        }
+    }
+}
+
+impl View for App {
+    fn body(&self) -> AnyView {
+        let mut stack = Stack::new();
+        stack.push(TermText::new(format!("Count: {}", self.count.get())));
+        stack.push(Button::new("Increment", {
+            let this = self.clone();
+            move || this.count.set(this.count.get() + 1)
+        }));
+        stack.push(
+            Button::new("Decrement", {
+                let this = self.clone();
+                move || this.count.set(this.count.get().saturating_sub(1))
+            })
+            .disabled(self.count.get() == 0),
+        );
+        stack.any_view()
+    }
+}
+
+fn main() -> std::io::Result<()> {
+    quill_termion::run(App::new())
+}
@@ -0,0 +1,88 @@
+use quill::*;
+use quill_derive::*;
+use quill_termion::*;
+use std::rc::Rc;
+
+#[derive(Debug, Clone, PartialEq, Hydrate)]
+struct App {
+    pub cpu: State<Series>,
+    pub mem: State<Series>,
+    pub sampled: State<f64>,
+}
+
+impl App {
+    fn new() -> Self {
+        App {
+            cpu: State::new(Rc::new(Vec::new())),
+            mem: State::new(Rc::new(Vec::new())),
+            sampled: State::new(0.0),
+        }
+    }
+
+    fn sample(&self, time: f64) {
+        let push = |series: &State<Series>, value: f64| {
+            series.update(|series| {
+                let series = Rc::make_mut(series);
+                series.push(value);
+                if series.len() > 200 {
+                    series.remove(0);
+                }
+            })
+        };
+        push(
+            &self.cpu,
+            50.0 + 40.0 * (time * 0.7).sin() * (time * 0.13).cos(),
+        );
+        push(&self.mem, 30.0 + 10.0 * (time * 0.2).sin() + time.min(30.0));
+    }
+}
+
+impl View for App {
+    fn body(&self) -> AnyView {
+        let cpu = self.cpu.get();
+        let mem = self.mem.get();
+        let last = |series: &Series| series.last().copied().unwrap_or(0.0);
+        let mut stack = Stack::new();
+        stack.push(TermText::new(format!("cpu {:5.1}%", last(&cpu))));
+        stack.push(Sparkline::new(cpu.clone()).width(60.0).range(0.0, 100.0));
+        stack.push(TermText::new(format!("mem {:5.1}%", last(&mem))));
+        stack.push(
+            Sparkline::new(mem.clone())
+                .width(60.0)
+                .range(0.0, 100.0)
+                .styled(Style::new().fg(Color::Magenta)),
+        );
+        stack.push(
+            BarChart::new(Rc::new(vec![
+                (String::from("cpu"), last(&cpu)),
+                (String::from("memory"), last(&mem)),
+                (String::from("disk"), 72.5),
+            ]))
+            .width(60.0)
+            .max(100.0),
+        );
+        stack.push(
+            LineChart::new()
+                .with_series(cpu, Color::Cyan)
+                .with_series(mem, Color::Magenta)
+                .range(0.0, 100.0)
+                .width(60.0)
+                .height(12.0),
+        );
+        stack.any_view()
+    }
+
+    fn event(&self, _size: Size, _offset: Offset, _children: &[ViewTree], event: &Event) -> bool {
+        if let Event::Frame(time) = *event {
+            if time - self.sampled.get() >= 0.1 {
+                self.sampled.set(time);
+                self.sample(time);
+            }
+        }
+        false
+    }
+}
+
+fn main() -> std::io::Result<()> {
+    quill_termion::run(App::new())
+}
@@ -0,0 +1,42 @@
+use quill::*;
+use quill_derive::*;
+use quill_termion::*;
+
+#[derive(Debug, Clone, PartialEq, Hydrate)]
+struct App {
+    pub verbose: State<bool>,
+    pub dark_mode: State<bool>,
+    pub level: State<usize>,
+}
+
+impl App {
+    fn new() -> Self {
+        App {
+            verbose: State::new(false),
+            dark_mode: State::new(true),
+            level: State::new(1),
+        }
+    }
+}
+
+impl View for App {
+    fn body(&self) -> AnyView {
+        let mut stack = Stack::new();
+        stack.push(Checkbox::new(self.verbose.clone(), "Verbose output"));
+        stack.push(Toggle::new(self.dark_mode.clone(), "Dark mode"));
+        stack.push(TermText::new("Log level:"));
+        stack.push(
+            RadioGroup::new(self.level.clone())
+                .with_option("Debug")
+                .with_option("Info")
+                .with_option("Warning")
+                .with_option("Error")
+                .disabled(!self.verbose.get()),
+        );
+        stack.any_view()
+    }
+}
+
+fn main() -> std::io::Result<()> {
+    quill_termion::run(App::new())
+}
@@ -0,0 +1,123 @@
+use quill::*;
+use quill_derive::*;
+use quill_termion::*;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Popup {
+    None,
+    Confirm,
+    Menu,
+}
+
+#[derive(Debug, Clone, PartialEq, Hydrate)]
+struct App {
+    pub files: State<Vec<String>>,
+    pub selected: State<usize>,
+    pub popup: State<Popup>,
+    pub sort: State<usize>,
+    pub anchor: Anchor,
+}
+
+impl App {
+    fn new() -> Self {
+        App {
+            files: State::new(
+                (1..=20)
+                    .map(|nth| format!("report-{:02}.csv", nth))
+                    .collect(),
+            ),
+            selected: State::new(0),
+            popup: State::new(Popup::None),
+            sort: State::new(0),
+            anchor: Anchor::new(),
+        }
+    }
+
+    fn close(&self) {
+        self.popup.set(Popup::None);
+    }
+
+    fn delete_selected(&self) {
+        let nth = self.selected.get();
+        self.files.update(|files| {
+            if nth < files.len() {
+                files.remove(nth);
+            }
+        });
+        self.close();
+    }
+}
+
+const SORTS: [&str; 3] = ["Name", "Size", "Modified"];
+
+impl View for App {
+    fn body(&self) -> AnyView {
+        let mut stack = Stack::new();
+        stack.push(TermText::new(format!(
+            "Sorted by {}. Tab moves focus, Esc closes popups.",
+            SORTS[self.sort.get()]
+        )));
+        stack.push(Button::new("Delete…", {
+            let this = self.clone();
+            move || this.popup.set(Popup::Confirm)
+        }));
+        stack.push(Anchored::new(
+            self.anchor.clone(),
+            Button::new("Sort by ▾", {
+                let this = self.clone();
+                move || this.popup.set(Popup::Menu)
+            }),
+        ));
+        stack.push(
+            List::new(self.selected.clone())
+                .with_items(self.files.get())
+                .height(12.0),
+        );
+
+        let layer = match self.popup.get() {
+            Popup::None => None,
+            Popup::Confirm => {
+                let file = self
+                    .files
+                    .with(|files| files.get(self.selected.get()).cloned());
+                let message = match file {
+                    Some(file) => format!("Delete {}?\nThis can't be undone.", file),
+                    None => String::from("Nothing to delete."),
+                };
+                let dialog = Dialog::new(message)
+                    .button("Cancel", {
+                        let this = self.clone();
+                        move || this.close()
+                    })
+                    .button("Delete", {
+                        let this = self.clone();
+                        move || this.delete_selected()
+                    });
+                Some(Modal::new(dialog).title("Confirm").on_dismiss({
+                    let this = self.clone();
+                    move || this.close()
+                }))
+            }
+            Popup::Menu => {
+                let menu = List::new(self.sort.clone()).with_items(SORTS).on_activate({
+                    let this = self.clone();
+                    move |_| this.close()
+                });
+                Some(
+                    Modal::new(menu)
+                        .below(self.anchor.clone())
+                        .dismiss_on_outside_click()
+                        .on_dismiss({
+                            let this = self.clone();
+                            move || this.close()
+                        }),
+                )
+            }
+        };
+        Overlay::new(stack).layer(layer).any_view()
+    }
+}
+
+fn main() -> std::io::Result<()> {
+    quill_termion::run(App::new())
+}
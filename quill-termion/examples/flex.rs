@@ -0,0 +1,68 @@
+use quill::*;
+use quill_derive::*;
+use quill_termion::*;
+
+#[derive(Debug, Clone, PartialEq, Hydrate)]
+struct App {}
+
+impl View for App {
+    fn body(&self) -> AnyView {
+        let label = |text: &str| TermText::new(text).styled(Style::new().reverse());
+        let mut column = Flex::column().gap(1.0);
+        // The middle child takes all the free space.
+        column.push(
+            Flex::row()
+                .gap(1.0)
+                .with_child(label("left"))
+                .with_child(
+                    label("grows")
+                        .align(Alignment::CENTER)
+                        .frame(None, 1.0, Alignment::CENTER)
+                        .flex(1.0),
+                )
+                .with_child(label("right")),
+        );
+        column.push(
+            Flex::row()
+                .justify(Justify::SpaceBetween)
+                .with_child(label("space"))
+                .with_child(label("between"))
+                .with_child(label("items")),
+        );
+        column.push(
+            Flex::row()
+                .justify(Justify::SpaceEvenly)
+                .with_child(label("space"))
+                .with_child(label("evenly")),
+        );
+        // When there is not enough room, the low priority child gives way.
+        column.push(
+            Flex::row()
+                .with_child(
+                    label("This title keeps its room")
+                        .frame(None, 1.0, Alignment::LEADING)
+                        .layout_priority(1.0),
+                )
+                .with_child(
+                    TermText::new(" and this long description is cut short first").frame(
+                        None,
+                        1.0,
+                        Alignment::LEADING,
+                    ),
+                ),
+        );
+        let mut tags = Flex::row().wrap().gap(1.0);
+        for tag in [
+            "rust", "terminal", "layout", "flex", "wrap", "justify", "align", "grid", "modal",
+            "chart", "tabs", "tree", "table", "list",
+        ] {
+            tags.push(label(tag));
+        }
+        column.push(tags.frame(None, None, Alignment::LEADING).max_width(30.0));
+        column.any_view()
+    }
+}
+
+fn main() -> std::io::Result<()> {
+    quill_termion::run(App {})
+}
@@ -0,0 +1,56 @@
+use quill::*;
+use quill_derive::*;
+use quill_termion::*;
+
+#[derive(Debug, Clone, PartialEq, Hydrate)]
+struct App {}
+
+impl View for App {
+    fn body(&self) -> AnyView {
+        let title = Style::new().fg(Color::Cyan).bold();
+        let mut grid = Grid::new()
+            .columns([
+                Track::Fixed(12.0),
+                Track::Fraction(1.0),
+                Track::Fraction(2.0),
+            ])
+            .rows([
+                Track::Auto,
+                Track::Auto,
+                Track::Auto,
+                Track::Auto,
+                Track::Auto,
+                Track::Fraction(1.0),
+            ])
+            .gap(1.0, 2.0);
+        grid.push(
+            GridCell::new(TermText::new("Dashboard").styled(title))
+                .span(1, 3)
+                .align(Alignment::TOP),
+        );
+        for (nth, name) in ["CPU", "Memory", "Disk", "Network"].iter().enumerate() {
+            grid.push(GridCell::new(TermText::new(name)).at(1 + nth, 0));
+            grid.push(
+                GridCell::new(ProgressBar::new(0.2 + 0.2 * nth as f64).show_percent())
+                    .at(1 + nth, 1),
+            );
+        }
+        grid.push(
+            GridCell::new(TermText::new("Spans four rows"))
+                .at(1, 2)
+                .span(4, 1)
+                .align(Alignment::CENTER),
+        );
+        grid.push(
+            GridCell::new(TermText::new("Press q to quit"))
+                .at(6, 0)
+                .span(1, 3)
+                .align(Alignment::TRAILING),
+        );
+        grid.any_view()
+    }
+}
+
+fn main() -> std::io::Result<()> {
+    quill_termion::run(App {})
+}
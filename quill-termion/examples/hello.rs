@@ -32,7 +32,6 @@ impl View for App {
         }));
         stack.push(CountClicks::new().padding(self.padding.get() as f64));
         stack.push(CountClicks::new());
-        stack.push(Stats::new());
         stack.any_view()
     }
 }
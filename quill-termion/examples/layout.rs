@@ -0,0 +1,39 @@
+use quill::*;
+use quill_derive::*;
+use quill_termion::*;
+
+#[derive(Debug, Clone, PartialEq, Hydrate)]
+struct App {}
+
+impl View for App {
+    fn body(&self) -> AnyView {
+        let mut stack = Stack::new();
+        stack.push(
+            TermText::new("Fixed 24x3, centered")
+                .frame(24.0, 3.0, Alignment::CENTER)
+                .boxed(),
+        );
+        stack.push(
+            TermText::new("Full width, trailing")
+                .frame(None, None, Alignment::TRAILING)
+                .max_width(f64::INFINITY),
+        );
+        stack.push(
+            TermText::new("At least 30 wide")
+                .frame(None, None, Alignment::LEADING)
+                .min_width(30.0),
+        );
+        stack.push(TermText::new("Nudged right").offset(4.0, 0.0));
+        stack.push(
+            TermText::new("This line is longer than the space it gets")
+                .fixed_size()
+                .frame(20.0, 1.0, Alignment::LEADING),
+        );
+        stack.push(TermText::new("Bottom right").align(Alignment::BOTTOM_TRAILING));
+        stack.any_view()
+    }
+}
+
+fn main() -> std::io::Result<()> {
+    quill_termion::run(App {})
+}
@@ -0,0 +1,43 @@
+use quill::*;
+use quill_derive::*;
+use quill_termion::*;
+
+#[derive(Debug, Clone, PartialEq, Hydrate)]
+struct App {
+    pub selected: State<usize>,
+    pub opened: State<Option<usize>>,
+}
+
+impl App {
+    fn new() -> Self {
+        App {
+            selected: State::new(0),
+            opened: State::new(None),
+        }
+    }
+}
+
+impl View for App {
+    fn body(&self) -> AnyView {
+        let mut stack = Stack::new();
+        let status = match self.opened.get() {
+            Some(nth) => format!("Opened job #{}", nth),
+            None => String::from("Enter or double-click opens a job"),
+        };
+        stack.push(TermText::new(status));
+        stack.push(
+            List::new(self.selected.clone())
+                .with_items((0..100).map(|nth| format!("job #{}", nth)))
+                .height(10.0)
+                .on_activate({
+                    let this = self.clone();
+                    move |nth| this.opened.set(Some(nth))
+                }),
+        );
+        stack.any_view()
+    }
+}
+
+fn main() -> std::io::Result<()> {
+    quill_termion::run(App::new())
+}
@@ -0,0 +1,40 @@
+use quill::*;
+use quill_derive::*;
+use quill_termion::*;
+
+const LINES: usize = 100_000;
+
+#[derive(Debug, Clone, PartialEq, Hydrate)]
+struct App {
+    pub scroll: State<usize>,
+}
+
+impl App {
+    fn new() -> Self {
+        App {
+            scroll: State::new(0),
+        }
+    }
+}
+
+impl View for App {
+    fn body(&self) -> AnyView {
+        let mut stack = Stack::new();
+        stack.push(TermText::new(format!(
+            "Line {} of {} (mouse wheel scrolls)",
+            self.scroll.get() + 1,
+            LINES
+        )));
+        stack.push(
+            VirtualList::new(LINES, 1.0, |nth| {
+                TermText::new(format!("{:>6} | log message number {}", nth, nth)).any_view()
+            })
+            .scroll(self.scroll.clone()),
+        );
+        stack.any_view()
+    }
+}
+
+fn main() -> std::io::Result<()> {
+    quill_termion::run(App::new())
+}
@@ -0,0 +1,72 @@
+use quill::*;
+use quill_derive::*;
+use quill_termion::*;
+
+#[derive(Debug, Clone, PartialEq, Hydrate)]
+struct App {
+    pub chosen: State<String>,
+}
+
+impl View for App {
+    fn body(&self) -> AnyView {
+        let mut menu = Menu::default();
+        for item in ["Open…", "Save", "Save as…", "Export to PDF…", "Quit"] {
+            let chosen = self.chosen.clone();
+            // Items fill the width of the menu, so the whole row is
+            // clickable.
+            let row = TermText::new(format!(" {} ", item))
+                .frame(None, None, Alignment::LEADING)
+                .max_width(f64::INFINITY)
+                .on_click(move || chosen.set(item.to_string()));
+            menu.items.push(row.any_view());
+        }
+        Stack::new()
+            .with_child(menu.boxed())
+            .with_child(TermText::new(format!("Chosen: {}", self.chosen.get())))
+            .any_view()
+    }
+}
+
+/// A column of items that all get the width of the widest one, and no more:
+/// the width is negotiated with the items before they are laid out.
+#[derive(Debug, Clone, Default, PartialEq, Hydrate)]
+struct Menu {
+    pub items: Vec<AnyView>,
+}
+
+impl View for Menu {
+    fn children(&self) -> Vec<AnyView> {
+        self.items.clone()
+    }
+
+    fn layout(&self, children: &[ViewTree], constraint: Constraint) -> Size {
+        let width = children
+            .iter()
+            .map(|item| item.max_intrinsic_width(1.0))
+            .fold(0.0, f64::max)
+            .min(constraint.max_width());
+        let mut height = 0.0;
+        for item in children {
+            item.layout(Constraint::tight(Size::new(
+                width,
+                item.max_intrinsic_height(width),
+            )));
+            height += item.view.size.get().height;
+        }
+        constraint.constrain(Size::new(width, height))
+    }
+
+    fn set_offset(&self, children: &[ViewTree], offset: Offset) {
+        let mut y = offset.y;
+        for item in children {
+            item.set_offset(Offset::new(offset.x, y));
+            y += item.view.size.get().height;
+        }
+    }
+}
+
+fn main() -> std::io::Result<()> {
+    quill_termion::run(App {
+        chosen: State::new(String::new()),
+    })
+}
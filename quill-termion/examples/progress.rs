@@ -0,0 +1,68 @@
+use quill::*;
+use quill_derive::*;
+use quill_termion::*;
+
+#[derive(Debug, Clone, PartialEq, Hydrate)]
+struct App {}
+
+impl View for App {
+    fn body(&self) -> AnyView {
+        let mut stack = Stack::new();
+        stack.push(Spinner::new("Connecting…"));
+        stack.push(Spinner::new("Line").frames(SpinnerFrames::Line));
+        stack.push(Spinner::new("Arc").frames(SpinnerFrames::Arc));
+        stack.push(
+            Spinner::new("Quadrant")
+                .frames(SpinnerFrames::Quadrant)
+                .interval(0.2),
+        );
+        stack.push(Spinner::new("Bounce").frames(SpinnerFrames::Bounce));
+        stack.push(ProgressBar::indeterminate().width(30.0));
+        stack.push(Job::new("small.tar", 3.0));
+        stack.push(Job::new("medium.tar", 8.0));
+        stack.push(Job::new("large.tar", 20.0));
+        stack.any_view()
+    }
+}
+
+/// A fake download that completes after `seconds` of the frame clock.
+#[derive(Debug, Clone, PartialEq, Hydrate)]
+struct Job {
+    pub name: String,
+    pub seconds: f64,
+    pub progress: State<f64>,
+}
+
+impl Job {
+    fn new(name: &str, seconds: f64) -> Self {
+        Job {
+            name: name.to_string(),
+            seconds,
+            progress: State::new(0.0),
+        }
+    }
+}
+
+impl View for Job {
+    fn body(&self) -> AnyView {
+        let mut stack = Stack::new();
+        stack.push(TermText::new(format!("{:<12}", self.name)));
+        stack.push(
+            ProgressBar::new(self.progress.get())
+                .width(30.0)
+                .show_percent(),
+        );
+        stack.any_view()
+    }
+
+    fn event(&self, _size: Size, _offset: Offset, _children: &[ViewTree], event: &Event) -> bool {
+        if let Event::Frame(time) = *event {
+            self.progress.set((time / self.seconds).min(1.0));
+        }
+        false
+    }
+}
+
+fn main() -> std::io::Result<()> {
+    quill_termion::run(App {})
+}
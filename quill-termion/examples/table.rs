@@ -0,0 +1,52 @@
+use quill::*;
+use quill_derive::*;
+use quill_termion::*;
+use std::rc::Rc;
+
+#[derive(Debug, Clone, PartialEq, Hydrate)]
+struct App {
+    pub selected: State<usize>,
+    pub rows: Rc<Vec<Vec<String>>>,
+}
+
+impl App {
+    fn new() -> Self {
+        let rows = (0..10_000)
+            .map(|pid| {
+                vec![
+                    format!("{}", 1000 + pid),
+                    format!("worker-{}", pid % 17),
+                    format!("{:.1}", (pid * 37 % 1000) as f64 / 10.0),
+                    format!("/usr/bin/worker --shard {} --verbose", pid),
+                ]
+            })
+            .collect();
+        App {
+            selected: State::new(0),
+            rows: Rc::new(rows),
+        }
+    }
+}
+
+impl View for App {
+    fn body(&self) -> AnyView {
+        let mut stack = Stack::new();
+        stack.push(TermText::new(format!(
+            "Selected row: {} (click headers to sort, drag borders to resize)",
+            self.selected.get()
+        )));
+        stack.push(
+            Table::new(self.selected.clone())
+                .with_column(Column::new("PID", ColumnWidth::Fixed(6.0)).align(TextAlign::Right))
+                .with_column(Column::new("Name", ColumnWidth::Percent(20.0)))
+                .with_column(Column::new("CPU %", ColumnWidth::Fixed(7.0)).align(TextAlign::Right))
+                .with_column(Column::new("Command", ColumnWidth::Flex(1.0)).sortable(false))
+                .rows(self.rows.clone()),
+        );
+        stack.any_view()
+    }
+}
+
+fn main() -> std::io::Result<()> {
+    quill_termion::run(App::new())
+}
@@ -0,0 +1,60 @@
+use quill::*;
+use quill_derive::*;
+use quill_termion::*;
+
+#[derive(Debug, Clone, PartialEq, Hydrate)]
+struct App {
+    pub tab: State<usize>,
+    pub name: State<String>,
+    pub notes: State<String>,
+    pub job: State<usize>,
+}
+
+impl App {
+    fn new() -> Self {
+        App {
+            tab: State::new(0),
+            name: State::new(String::new()),
+            notes: State::new(String::new()),
+            job: State::new(0),
+        }
+    }
+}
+
+impl View for App {
+    fn body(&self) -> AnyView {
+        let mut stack = Stack::new();
+        stack.push(TermText::new(
+            "Ctrl-PgUp/PgDn or Alt-1..9 switch tabs, Tab moves focus",
+        ));
+        let mut tabs = Tabs::new(self.tab.clone())
+            .with_tab(
+                "Profile",
+                TextField::new(self.name.clone())
+                    .placeholder("Name")
+                    .width(30.0),
+            )
+            .with_tab(
+                "Notes",
+                TextArea::new(self.notes.clone()).width(40.0).height(8.0),
+            )
+            .with_tab(
+                "Jobs",
+                List::new(self.job.clone())
+                    .with_items((0..50).map(|nth| format!("job #{}", nth)))
+                    .height(8.0),
+            );
+        for nth in 4..=12 {
+            tabs = tabs.with_tab(
+                format!("Extra {}", nth),
+                TermText::new(format!("Page {}", nth)),
+            );
+        }
+        stack.push(tabs);
+        stack.any_view()
+    }
+}
+
+fn main() -> std::io::Result<()> {
+    quill_termion::run(App::new())
+}
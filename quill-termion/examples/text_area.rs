@@ -0,0 +1,43 @@
+use quill::*;
+use quill_derive::*;
+use quill_termion::*;
+
+#[derive(Debug, Clone, PartialEq, Hydrate)]
+struct App {
+    pub config: State<String>,
+    pub wrap: State<bool>,
+}
+
+impl App {
+    fn new() -> Self {
+        App {
+            config: State::new(String::from(
+                "[server]\nhost = \"localhost\"\nport = 8080\n\n[logging]\nlevel = \"info\"\n",
+            )),
+            wrap: State::new(false),
+        }
+    }
+}
+
+impl View for App {
+    fn body(&self) -> AnyView {
+        let mut stack = Stack::new();
+        let wrap = if self.wrap.get() { "on" } else { "off" };
+        stack.push(TermText::new(format!("Soft wrap: {}", wrap)).on_click({
+            let this = self.clone();
+            move || this.wrap.set(!this.wrap.get())
+        }));
+        stack.push(
+            TextArea::new(self.config.clone())
+                .width(40.0)
+                .height(10.0)
+                .soft_wrap(self.wrap.get())
+                .boxed(),
+        );
+        stack.any_view()
+    }
+}
+
+fn main() -> std::io::Result<()> {
+    quill_termion::run(App::new())
+}
@@ -0,0 +1,44 @@
+use quill::*;
+use quill_derive::*;
+use quill_termion::*;
+
+#[derive(Debug, Clone, PartialEq, Hydrate)]
+struct App {
+    pub name: State<String>,
+    pub password: State<String>,
+    pub submitted: State<String>,
+}
+
+impl App {
+    fn new() -> Self {
+        App {
+            name: State::new(String::new()),
+            password: State::new(String::new()),
+            submitted: State::new(String::new()),
+        }
+    }
+}
+
+impl View for App {
+    fn body(&self) -> AnyView {
+        let mut stack = Stack::new();
+        stack.push(TermText::new("Name:"));
+        stack.push(
+            TextField::new(self.name.clone())
+                .placeholder("Your name")
+                .width(30.0)
+                .on_submit({
+                    let this = self.clone();
+                    move |name| this.submitted.set(format!("Hello, {}!", name))
+                }),
+        );
+        stack.push(TermText::new("Password:"));
+        stack.push(TextField::new(self.password.clone()).password().width(30.0));
+        stack.push(TermText::new(self.submitted.get()));
+        stack.any_view()
+    }
+}
+
+fn main() -> std::io::Result<()> {
+    quill_termion::run(App::new())
+}
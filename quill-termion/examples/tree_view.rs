@@ -0,0 +1,66 @@
+use quill::*;
+use quill_derive::*;
+use quill_termion::*;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq, Hydrate)]
+struct App {
+    pub selected: State<TreePath>,
+    pub opened: State<String>,
+}
+
+impl App {
+    fn new() -> Self {
+        App {
+            selected: State::new(Vec::new()),
+            opened: State::new(String::from("Enter or double-click opens a file")),
+        }
+    }
+}
+
+// Directories are read only when they are expanded.
+fn entry(path: PathBuf) -> TreeNode {
+    let name = path.file_name().map_or_else(
+        || path.display().to_string(),
+        |name| name.to_string_lossy().into_owned(),
+    );
+    let node = TreeNode::new(name);
+    if path.is_dir() {
+        node.lazy(move || read_dir(&path))
+    } else {
+        node
+    }
+}
+
+fn read_dir(path: &Path) -> Vec<TreeNode> {
+    let mut paths: Vec<PathBuf> = match std::fs::read_dir(path) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    paths.sort_by_key(|path| (!path.is_dir(), path.file_name().map(|name| name.to_owned())));
+    paths.into_iter().map(entry).collect()
+}
+
+impl View for App {
+    fn body(&self) -> AnyView {
+        let mut stack = Stack::new();
+        stack.push(TermText::new(self.opened.get()));
+        stack.push(
+            TreeView::new(self.selected.clone())
+                .with_root(entry(PathBuf::from(".")).id("."))
+                .height(20.0)
+                .on_activate({
+                    let this = self.clone();
+                    move |path| this.opened.set(format!("Opened {}", path.join("/")))
+                }),
+        );
+        stack.any_view()
+    }
+}
+
+fn main() -> std::io::Result<()> {
+    quill_termion::run(App::new())
+}
@@ -0,0 +1,105 @@
+// The layout bounds debug mode: every view's rectangle is outlined over the
+// UI, colored by depth. Views whose size is outside the constraint they were
+// laid out with get a full red border instead.
+
+use crate::clip::write_clipped;
+use crate::style::{Color, Style};
+use quill::*;
+use std::io::Write;
+
+const DEPTH_COLORS: [Color; 6] = [
+    Color::Blue,
+    Color::Green,
+    Color::Yellow,
+    Color::Magenta,
+    Color::Cyan,
+    Color::LightBlue,
+];
+
+/// Outline `tree` and its descendants, clipped to `screen`.
+pub fn render_bounds(tree: &ViewTree, screen: Rect, out: &mut impl Write) {
+    let mut output = Vec::new();
+    outline_tree(tree, 0, &mut output);
+    // Overflows are drawn last so nested outlines do not hide them.
+    for view in tree.flatten().into_iter().filter(AnyView::overflows) {
+        outline(
+            view.rect(),
+            Style::new().fg(Color::Red).bold(),
+            true,
+            &mut output,
+        );
+    }
+    write_clipped(&String::from_utf8_lossy(&output), screen, out);
+    write!(out, "{}", termion::style::Reset).unwrap();
+}
+
+fn outline_tree(tree: &ViewTree, depth: usize, out: &mut Vec<u8>) {
+    let style = Style::new().fg(DEPTH_COLORS[depth % DEPTH_COLORS.len()]);
+    outline(tree.view.rect(), style, false, out);
+    for child in &tree.children {
+        outline_tree(child, depth + 1, out);
+    }
+}
+
+/// Draw the corners of `rect`, or its whole border with `full`. Rows and
+/// columns of a single cell are marked at both ends.
+pub(crate) fn outline(rect: Rect, style: Style, full: bool, out: &mut Vec<u8>) {
+    let (width, height) = (rect.size.width as usize, rect.size.height as usize);
+    if width == 0 || height == 0 {
+        return;
+    }
+    // Like `cursor::Goto`, but the position may be off screen; it is only
+    // read by `write_clipped`.
+    let goto = |x: usize, y: usize| {
+        format!(
+            "\x1b[{};{}H",
+            rect.top() as i64 + y as i64,
+            rect.left() as i64 + x as i64
+        )
+    };
+    write!(out, "{}", style).unwrap();
+    match (width, height) {
+        (1, 1) => write!(out, "{}□", goto(0, 0)).unwrap(),
+        (_, 1) => write!(
+            out,
+            "{}╶{}{}╴",
+            goto(0, 0),
+            rule(full, "─", width - 2),
+            goto(width - 1, 0)
+        )
+        .unwrap(),
+        (1, _) => write!(out, "{}╷{}╵", goto(0, 0), goto(0, height - 1)).unwrap(),
+        _ => {
+            write!(
+                out,
+                "{}┌{}{}┐",
+                goto(0, 0),
+                rule(full, "─", width - 2),
+                goto(width - 1, 0)
+            )
+            .unwrap();
+            if full {
+                for y in 1..height - 1 {
+                    write!(out, "{}│{}│", goto(0, y), goto(width - 1, y)).unwrap();
+                }
+            }
+            write!(
+                out,
+                "{}└{}{}┘",
+                goto(0, height - 1),
+                rule(full, "─", width - 2),
+                goto(width - 1, height - 1)
+            )
+            .unwrap();
+        }
+    }
+}
+
+// A line between two corners, or nothing when only corners are drawn.
+fn rule(full: bool, line: &str, length: usize) -> String {
+    if full {
+        line.repeat(length)
+    } else {
+        String::new()
+    }
+}
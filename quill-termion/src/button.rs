@@ -0,0 +1,128 @@
+use crate::style::Style;
+use crate::TermText;
+use quill::*;
+use std::rc::Rc;
+
+/// A clickable label. Fires when the mouse is released over the button after
+/// pressing it there, or on Enter/Space while focused.
+#[derive(Clone)]
+pub struct Button {
+    pub label: String,
+    pub cb: Rc<dyn Fn()>,
+    pub disabled: bool,
+    pressed: State<bool>,
+    hovered: State<bool>,
+    focused: State<bool>,
+}
+
+impl std::fmt::Debug for Button {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Button")
+            .field("label", &self.label)
+            .field("disabled", &self.disabled)
+            .field("pressed", &self.pressed)
+            .field("hovered", &self.hovered)
+            .field("focused", &self.focused)
+            .finish()
+    }
+}
+
+impl Button {
+    pub fn new(label: impl ToString, cb: impl Fn() + 'static) -> Self {
+        Button {
+            label: label.to_string(),
+            cb: Rc::new(cb),
+            disabled: false,
+            pressed: State::new(false),
+            hovered: State::new(false),
+            focused: State::new(false),
+        }
+    }
+
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+
+    pub fn style(&self) -> Style {
+        if self.disabled {
+            Style::new().dim()
+        } else if self.pressed.get() {
+            Style::new().reverse().bold()
+        } else if self.hovered.get() {
+            Style::new().reverse()
+        } else if self.focused.get() {
+            Style::new().bold().underline()
+        } else {
+            Style::new()
+        }
+    }
+}
+
+impl Hydrate for Button {
+    fn hydrate(&mut self, other: &Self) {
+        self.label = other.label.clone();
+        self.cb = other.cb.clone();
+        self.disabled = other.disabled;
+        if self.disabled {
+            self.pressed.set(false);
+        }
+    }
+}
+
+impl View for Button {
+    fn body(&self) -> AnyView {
+        TermText::new(format!("[ {} ]", self.label))
+            .styled(self.style())
+            .any_view()
+    }
+
+    fn focusable(&self) -> bool {
+        !self.disabled
+    }
+
+    fn set_focus(&self, focused: bool) {
+        self.focused.set(focused);
+    }
+
+    fn event(&self, size: Size, offset: Offset, _children: &[ViewTree], event: &Event) -> bool {
+        let bounds = Rect::new(offset, size);
+        let hover = |x, y| {
+            if self.hovered.get() != bounds.contains(x, y) {
+                self.hovered.set(bounds.contains(x, y));
+            }
+        };
+        if self.disabled {
+            return false;
+        }
+        match *event {
+            Event::MousePress(MouseButton::Left, x, y) if bounds.contains(x, y) => {
+                self.pressed.set(true);
+                hover(x, y);
+                true
+            }
+            Event::MouseHold(x, y) | Event::MouseMove(x, y) => {
+                hover(x, y);
+                false
+            }
+            Event::MouseRelease(x, y) => {
+                hover(x, y);
+                if !self.pressed.get() {
+                    return false;
+                }
+                self.pressed.set(false);
+                if bounds.contains(x, y) {
+                    (*self.cb)();
+                }
+                true
+            }
+            Event::KeyPress(KeyCode::Enter, mods) | Event::KeyPress(KeyCode::Char(' '), mods)
+                if mods.is_empty() =>
+            {
+                (*self.cb)();
+                true
+            }
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,534 @@
+use crate::progress::horizontal_bar;
+use crate::style::{Color, Style};
+use crate::text::{columns, str_width};
+use quill::*;
+use std::rc::Rc;
+use termion::cursor;
+
+/// A data series shared between the parent's state and the chart views.
+pub type Series = Rc<Vec<f64>>;
+
+// Bottom-aligned blocks from one to eight eighths of a cell.
+const LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+// Braille dot bits, by column and then row within a 2x4 cell.
+const BRAILLE: [[u8; 4]; 2] = [[0x01, 0x02, 0x04, 0x40], [0x08, 0x10, 0x20, 0x80]];
+
+// Width of a chart without a `width` when the space is unbounded.
+const DEFAULT_WIDTH: f64 = 40.0;
+
+// Rendered rows for a key and size, so unchanged data isn't drawn again.
+struct DrawCache<K> {
+    key: K,
+    size: (usize, usize),
+    rows: Rc<Vec<String>>,
+}
+
+impl<K> std::fmt::Debug for DrawCache<K> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DrawCache")
+            .field("size", &self.size)
+            .finish()
+    }
+}
+
+fn cached<K: PartialEq>(
+    cache: &State<Option<DrawCache<K>>>,
+    key: K,
+    size: (usize, usize),
+    draw: impl FnOnce() -> Vec<String>,
+) -> Rc<Vec<String>> {
+    let hit = cache.with(|cache| match cache {
+        Some(cache) if cache.size == size && cache.key == key => Some(cache.rows.clone()),
+        _ => None,
+    });
+    if let Some(rows) = hit {
+        return rows;
+    }
+    let rows = Rc::new(draw());
+    cache.set(Some(DrawCache {
+        key,
+        size,
+        rows: rows.clone(),
+    }));
+    rows
+}
+
+fn render_rows(rows: &[String], offset: Offset, screen: &mut impl std::io::Write) {
+    for (line, row) in rows.iter().enumerate() {
+        write!(
+            screen,
+            "{}{}{}",
+            cursor::Goto(offset.x as u16, offset.y as u16 + line as u16),
+            row,
+            termion::style::Reset
+        )
+        .unwrap();
+    }
+}
+
+fn bounds<'a>(values: impl Iterator<Item = &'a f64>) -> (f64, f64) {
+    let (min, max) = values
+        .filter(|value| value.is_finite())
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &value| {
+            (min.min(value), max.max(value))
+        });
+    if min > max {
+        (0.0, 1.0)
+    } else if min == max {
+        (min - 1.0, max + 1.0)
+    } else {
+        (min, max)
+    }
+}
+
+// Fewer decimals for larger magnitudes, so axis labels stay short.
+fn format_value(value: f64, range: f64) -> String {
+    let decimals = if range >= 100.0 {
+        0
+    } else if range >= 1.0 {
+        1
+    } else {
+        2
+    };
+    format!("{:.*}", decimals, value)
+}
+
+/// The latest values of a series as a row of block glyphs, one per column.
+#[derive(Debug, Clone)]
+pub struct Sparkline {
+    pub data: Series,
+    pub width: Option<f64>,
+    pub range: Option<(f64, f64)>,
+    pub style: Style,
+    cache: State<Option<DrawCache<SparkKey>>>,
+}
+
+type SparkKey = (Series, Option<(f64, f64)>, Style);
+
+impl Sparkline {
+    pub fn new(data: Series) -> Self {
+        Sparkline {
+            data,
+            width: None,
+            range: None,
+            style: Style::new().fg(Color::Cyan),
+            cache: State::new(None),
+        }
+    }
+
+    pub fn width(mut self, width: f64) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    /// Fix the value range instead of fitting it to the data.
+    pub fn range(mut self, min: f64, max: f64) -> Self {
+        self.range = Some((min, max));
+        self
+    }
+
+    pub fn styled(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    fn draw(&self, width: usize) -> Vec<String> {
+        let visible = &self.data[self.data.len().saturating_sub(width)..];
+        let (min, max) = self.range.unwrap_or_else(|| bounds(visible.iter()));
+        let glyphs: String = visible
+            .iter()
+            .map(|value| {
+                let level = ((value - min) / (max - min)).clamp(0.0, 1.0);
+                LEVELS[(level * (LEVELS.len() - 1) as f64).round() as usize]
+            })
+            .collect();
+        vec![format!("{}{}", self.style, glyphs)]
+    }
+
+    pub fn render(&self, size: Size, offset: Offset, screen: &mut impl std::io::Write) {
+        let width = columns(size.width);
+        let key = (self.data.clone(), self.range, self.style);
+        let rows = cached(&self.cache, key, (width, 1), || self.draw(width));
+        render_rows(&rows, offset, screen);
+    }
+}
+
+impl Hydrate for Sparkline {
+    fn hydrate(&mut self, other: &Self) {
+        self.data = other.data.clone();
+        self.width = other.width;
+        self.range = other.range;
+        self.style = other.style;
+    }
+}
+
+impl View for Sparkline {
+    fn children(&self) -> Vec<AnyView> {
+        Vec::new()
+    }
+
+    fn layout(&self, _children: &[ViewTree], constraint: Constraint) -> Size {
+        Size {
+            width: self
+                .width
+                .unwrap_or(self.data.len() as f64)
+                .min(constraint.max_width()),
+            height: 1.0,
+        }
+    }
+}
+
+/// Labelled horizontal bars, drawn in eighths of a cell and followed by
+/// their value.
+#[derive(Debug, Clone)]
+pub struct BarChart {
+    pub bars: Rc<Vec<(String, f64)>>,
+    pub width: Option<f64>,
+    pub max: Option<f64>,
+    pub style: Style,
+    cache: State<Option<DrawCache<BarKey>>>,
+}
+
+type BarKey = (Rc<Vec<(String, f64)>>, Option<f64>, Style);
+
+impl BarChart {
+    pub fn new(bars: Rc<Vec<(String, f64)>>) -> Self {
+        BarChart {
+            bars,
+            width: None,
+            max: None,
+            style: Style::new().fg(Color::Blue),
+            cache: State::new(None),
+        }
+    }
+
+    pub fn width(mut self, width: f64) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    /// The value of a full-length bar. Defaults to the largest value.
+    pub fn max(mut self, max: f64) -> Self {
+        self.max = Some(max);
+        self
+    }
+
+    pub fn styled(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    fn draw(&self, width: usize) -> Vec<String> {
+        let label_width = self
+            .bars
+            .iter()
+            .map(|(label, _)| str_width(label))
+            .max()
+            .unwrap_or(0);
+        let max = self.max.unwrap_or_else(|| {
+            self.bars
+                .iter()
+                .map(|&(_, value)| value)
+                .fold(0.0, f64::max)
+        });
+        let range = max.abs();
+        let values: Vec<String> = self
+            .bars
+            .iter()
+            .map(|&(_, value)| format_value(value, range))
+            .collect();
+        let value_width = values.iter().map(|value| value.len()).max().unwrap_or(0);
+        // "label │bar value"
+        let track = width.saturating_sub(label_width + 2 + 1 + value_width);
+        self.bars
+            .iter()
+            .zip(values)
+            .map(|((label, value), text)| {
+                let fill = if max > 0.0 {
+                    (value / max).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+                let bar = horizontal_bar(fill, track);
+                let pad = label_width - str_width(label);
+                format!(
+                    "{}{}{} │{}{}{} {}",
+                    Style::new(),
+                    label,
+                    " ".repeat(pad),
+                    self.style,
+                    bar,
+                    Style::new(),
+                    text
+                )
+            })
+            .collect()
+    }
+
+    pub fn render(&self, size: Size, offset: Offset, screen: &mut impl std::io::Write) {
+        let width = columns(size.width);
+        let height = columns(size.height);
+        let key = (self.bars.clone(), self.max, self.style);
+        let rows = cached(&self.cache, key, (width, height), || self.draw(width));
+        render_rows(&rows[..height.min(rows.len())], offset, screen);
+    }
+}
+
+impl Hydrate for BarChart {
+    fn hydrate(&mut self, other: &Self) {
+        self.bars = other.bars.clone();
+        self.width = other.width;
+        self.max = other.max;
+        self.style = other.style;
+    }
+}
+
+impl View for BarChart {
+    fn children(&self) -> Vec<AnyView> {
+        Vec::new()
+    }
+
+    fn layout(&self, _children: &[ViewTree], constraint: Constraint) -> Size {
+        Size {
+            width: self
+                .width
+                .unwrap_or(bounded(constraint.max_width(), DEFAULT_WIDTH))
+                .min(constraint.max_width()),
+            height: (self.bars.len() as f64).min(constraint.max_height()),
+        }
+    }
+}
+
+/// One or more series plotted as lines in braille dots, which gives each
+/// cell 2x4 points, with a labelled y axis on the left and an x axis below.
+#[derive(Debug, Clone)]
+pub struct LineChart {
+    pub series: Vec<(Series, Color)>,
+    pub width: Option<f64>,
+    pub height: Option<f64>,
+    pub range: Option<(f64, f64)>,
+    cache: State<Option<DrawCache<LineKey>>>,
+}
+
+type LineKey = (Vec<(Series, Color)>, Option<(f64, f64)>);
+
+impl Default for LineChart {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LineChart {
+    pub fn new() -> Self {
+        LineChart {
+            series: Vec::new(),
+            width: None,
+            height: None,
+            range: None,
+            cache: State::new(None),
+        }
+    }
+
+    pub fn with_series(mut self, data: Series, color: Color) -> Self {
+        self.series.push((data, color));
+        self
+    }
+
+    pub fn width(mut self, width: f64) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    pub fn height(mut self, height: f64) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    /// Fix the y range instead of fitting it to the data.
+    pub fn range(mut self, min: f64, max: f64) -> Self {
+        self.range = Some((min, max));
+        self
+    }
+
+    fn draw(&self, width: usize, height: usize) -> Vec<String> {
+        let (min, max) = self
+            .range
+            .unwrap_or_else(|| bounds(self.series.iter().flat_map(|(data, _)| data.iter())));
+        let labels = [
+            format_value(max, max - min),
+            format_value((min + max) / 2.0, max - min),
+            format_value(min, max - min),
+        ];
+        let axis = labels.iter().map(|label| label.len()).max().unwrap_or(0);
+        // The last row is the x axis, and the y axis takes a column.
+        let (cols, lines) = (width.saturating_sub(axis + 1), height.saturating_sub(1));
+        let mut dots = vec![vec![0u8; cols]; lines];
+        let mut colors: Vec<Vec<Option<Color>>> = vec![vec![None; cols]; lines];
+        let (dot_cols, dot_rows) = (cols * 2, lines * 4);
+        if dot_cols > 0 && dot_rows > 0 {
+            for (data, color) in self.series.iter() {
+                let point = |nth: usize| {
+                    let x = if data.len() > 1 {
+                        nth as f64 * (dot_cols - 1) as f64 / (data.len() - 1) as f64
+                    } else {
+                        0.0
+                    };
+                    let level = ((data[nth] - min) / (max - min)).clamp(0.0, 1.0);
+                    let y = (1.0 - level) * (dot_rows - 1) as f64;
+                    (x.round() as isize, y.round() as isize)
+                };
+                let mut plot = |x: isize, y: isize| {
+                    let (x, y) = (x as usize, y as usize);
+                    dots[y / 4][x / 2] |= BRAILLE[x % 2][y % 4];
+                    colors[y / 4][x / 2] = Some(*color);
+                };
+                let points: Vec<(isize, isize)> = (0..data.len())
+                    .filter(|&nth| data[nth].is_finite())
+                    .map(point)
+                    .collect();
+                if let [only] = points[..] {
+                    plot(only.0, only.1);
+                }
+                for pair in points.windows(2) {
+                    line(pair[0], pair[1], &mut plot);
+                }
+            }
+        }
+
+        let mut rows = Vec::with_capacity(height);
+        for (line, (dots, colors)) in dots.iter().zip(colors.iter()).enumerate() {
+            let label = match line {
+                0 => labels[0].as_str(),
+                _ if line == lines / 2 && lines > 2 => labels[1].as_str(),
+                _ if line + 1 == lines => labels[2].as_str(),
+                _ => "",
+            };
+            let tick = if label.is_empty() { '│' } else { '┤' };
+            let mut row = format!("{}{:>axis$}{}", Style::new().dim(), label, tick);
+            let mut current = None;
+            for (&bits, &color) in dots.iter().zip(colors.iter()) {
+                if color != current && bits != 0 {
+                    row.push_str(&Style::new().fg(color.unwrap_or(Color::White)).to_string());
+                    current = color;
+                }
+                row.push(char::from_u32(0x2800 + bits as u32).unwrap_or(' '));
+            }
+            rows.push(row);
+        }
+        if height > 0 {
+            rows.push(format!(
+                "{}{}└{}",
+                Style::new().dim(),
+                " ".repeat(axis),
+                "─".repeat(cols)
+            ));
+        }
+        rows
+    }
+
+    pub fn render(&self, size: Size, offset: Offset, screen: &mut impl std::io::Write) {
+        let width = columns(size.width);
+        let height = columns(size.height);
+        let key = (self.series.clone(), self.range);
+        let rows = cached(&self.cache, key, (width, height), || {
+            self.draw(width, height)
+        });
+        render_rows(&rows, offset, screen);
+    }
+}
+
+// Bresenham's line between two dots, inclusive.
+fn line(from: (isize, isize), to: (isize, isize), plot: &mut impl FnMut(isize, isize)) {
+    let (mut x, mut y) = from;
+    let (dx, dy) = ((to.0 - x).abs(), -(to.1 - y).abs());
+    let (sx, sy) = ((to.0 - x).signum(), (to.1 - y).signum());
+    let mut err = dx + dy;
+    loop {
+        plot(x, y);
+        if (x, y) == to {
+            break;
+        }
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            x += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            y += sy;
+        }
+    }
+}
+
+impl Hydrate for LineChart {
+    fn hydrate(&mut self, other: &Self) {
+        self.series = other.series.clone();
+        self.width = other.width;
+        self.height = other.height;
+        self.range = other.range;
+    }
+}
+
+impl View for LineChart {
+    fn children(&self) -> Vec<AnyView> {
+        Vec::new()
+    }
+
+    fn layout(&self, _children: &[ViewTree], constraint: Constraint) -> Size {
+        Size {
+            width: self
+                .width
+                .unwrap_or(bounded(constraint.max_width(), DEFAULT_WIDTH))
+                .min(constraint.max_width()),
+            height: self.height.unwrap_or(10.0).min(constraint.max_height()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data() -> Series {
+        Rc::new(vec![1.0, 3.0, 2.0, 5.0])
+    }
+
+    /// Lay `view` out in unbounded space, and draw it at that size and at an
+    /// unbounded width. Returns the laid out size.
+    fn unbounded<V: View + Clone>(view: V, render: impl Fn(&V, Size, &mut Vec<u8>)) -> Size {
+        let tree = ViewTree::new(view.clone().any_view());
+        tree.layout(Constraint::unbounded());
+        let size = tree.view.size.get();
+        let mut screen = Vec::new();
+        render(&view, size, &mut screen);
+        render(&view, Size::new(f64::INFINITY, size.height), &mut screen);
+        size
+    }
+
+    #[test]
+    fn sparkline_in_unbounded_space() {
+        let size = unbounded(Sparkline::new(data()), |view, size, screen| {
+            view.render(size, Offset::new(1.0, 1.0), screen)
+        });
+        assert_eq!(size, Size::new(4.0, 1.0));
+    }
+
+    #[test]
+    fn bar_chart_in_unbounded_space() {
+        let bars = Rc::new(vec![("a".to_string(), 1.0), ("b".to_string(), 2.0)]);
+        let size = unbounded(BarChart::new(bars), |view, size, screen| {
+            view.render(size, Offset::new(1.0, 1.0), screen)
+        });
+        assert_eq!(size, Size::new(DEFAULT_WIDTH, 2.0));
+    }
+
+    #[test]
+    fn line_chart_in_unbounded_space() {
+        let chart = LineChart::new().with_series(data(), Color::Green);
+        let size = unbounded(chart, |view, size, screen| {
+            view.render(size, Offset::new(1.0, 1.0), screen)
+        });
+        assert_eq!(size, Size::new(DEFAULT_WIDTH, 10.0));
+    }
+}
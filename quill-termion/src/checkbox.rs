@@ -0,0 +1,249 @@
+use crate::style::{Color, Style};
+use crate::TermText;
+use quill::*;
+
+// Shared input handling for views that flip a boolean: a click anywhere on
+// the view (glyph or label), or Enter/Space while focused.
+fn toggle_event(checked: &State<bool>, size: Size, offset: Offset, event: &Event) -> bool {
+    match *event {
+        Event::MousePress(MouseButton::Left, x, y) if Rect::new(offset, size).contains(x, y) => {
+            checked.set(!checked.get());
+            true
+        }
+        Event::KeyPress(KeyCode::Enter, mods) | Event::KeyPress(KeyCode::Char(' '), mods)
+            if mods.is_empty() =>
+        {
+            checked.set(!checked.get());
+            true
+        }
+        _ => false,
+    }
+}
+
+fn label_style(focused: bool, disabled: bool) -> Style {
+    if disabled {
+        Style::new().dim()
+    } else if focused {
+        Style::new().bold().underline()
+    } else {
+        Style::new()
+    }
+}
+
+/// `[x] label`, bound to a `State<bool>`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Checkbox {
+    pub checked: State<bool>,
+    pub label: String,
+    pub disabled: bool,
+    focused: State<bool>,
+}
+
+impl Checkbox {
+    pub fn new(checked: State<bool>, label: impl ToString) -> Self {
+        Checkbox {
+            checked,
+            label: label.to_string(),
+            disabled: false,
+            focused: State::new(false),
+        }
+    }
+
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+}
+
+impl Hydrate for Checkbox {
+    fn hydrate(&mut self, other: &Self) {
+        self.checked = other.checked.clone();
+        self.label = other.label.clone();
+        self.disabled = other.disabled;
+    }
+}
+
+impl View for Checkbox {
+    fn body(&self) -> AnyView {
+        let glyph = if self.checked.get() { "[x]" } else { "[ ]" };
+        TermText::new(format!("{} {}", glyph, self.label))
+            .styled(label_style(self.focused.get(), self.disabled))
+            .any_view()
+    }
+
+    fn focusable(&self) -> bool {
+        !self.disabled
+    }
+
+    fn set_focus(&self, focused: bool) {
+        self.focused.set(focused);
+    }
+
+    fn event(&self, size: Size, offset: Offset, _children: &[ViewTree], event: &Event) -> bool {
+        !self.disabled && toggle_event(&self.checked, size, offset, event)
+    }
+}
+
+/// An on/off switch, bound to a `State<bool>`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Toggle {
+    pub on: State<bool>,
+    pub label: String,
+    pub disabled: bool,
+    focused: State<bool>,
+}
+
+impl Toggle {
+    pub fn new(on: State<bool>, label: impl ToString) -> Self {
+        Toggle {
+            on,
+            label: label.to_string(),
+            disabled: false,
+            focused: State::new(false),
+        }
+    }
+
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+}
+
+impl Hydrate for Toggle {
+    fn hydrate(&mut self, other: &Self) {
+        self.on = other.on.clone();
+        self.label = other.label.clone();
+        self.disabled = other.disabled;
+    }
+}
+
+impl View for Toggle {
+    fn body(&self) -> AnyView {
+        let mut style = label_style(self.focused.get(), self.disabled);
+        let glyph = if self.on.get() {
+            if !self.disabled {
+                style = style.fg(Color::Green);
+            }
+            "(━●)"
+        } else {
+            "(●━)"
+        };
+        TermText::new(format!("{} {}", glyph, self.label))
+            .styled(style)
+            .any_view()
+    }
+
+    fn focusable(&self) -> bool {
+        !self.disabled
+    }
+
+    fn set_focus(&self, focused: bool) {
+        self.focused.set(focused);
+    }
+
+    fn event(&self, size: Size, offset: Offset, _children: &[ViewTree], event: &Event) -> bool {
+        !self.disabled && toggle_event(&self.on, size, offset, event)
+    }
+}
+
+/// A single choice out of `options`, bound to the index of the selected one.
+/// The arrow keys move the selection while the group is focused.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RadioGroup {
+    pub selected: State<usize>,
+    pub options: Vec<String>,
+    pub disabled: bool,
+    focused: State<bool>,
+}
+
+impl RadioGroup {
+    pub fn new(selected: State<usize>) -> Self {
+        RadioGroup {
+            selected,
+            options: Vec::new(),
+            disabled: false,
+            focused: State::new(false),
+        }
+    }
+
+    pub fn with_option(mut self, option: impl ToString) -> Self {
+        self.options.push(option.to_string());
+        self
+    }
+
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+
+    fn select(&self, nth: usize) {
+        if nth < self.options.len() && nth != self.selected.get() {
+            self.selected.set(nth);
+        }
+    }
+}
+
+impl Hydrate for RadioGroup {
+    fn hydrate(&mut self, other: &Self) {
+        self.selected = other.selected.clone();
+        self.options = other.options.clone();
+        self.disabled = other.disabled;
+    }
+}
+
+impl View for RadioGroup {
+    fn body(&self) -> AnyView {
+        let selected = self.selected.get();
+        let mut stack = Stack::new();
+        for (nth, option) in self.options.iter().enumerate() {
+            let glyph = if nth == selected { "(•)" } else { "( )" };
+            let style = label_style(self.focused.get() && nth == selected, self.disabled);
+            stack.push(TermText::new(format!("{} {}", glyph, option)).styled(style));
+        }
+        stack.any_view()
+    }
+
+    fn focusable(&self) -> bool {
+        !self.disabled && !self.options.is_empty()
+    }
+
+    fn set_focus(&self, focused: bool) {
+        self.focused.set(focused);
+    }
+
+    fn event(&self, _size: Size, _offset: Offset, children: &[ViewTree], event: &Event) -> bool {
+        if self.disabled {
+            return false;
+        }
+        let selected = self.selected.get();
+        match *event {
+            Event::MousePress(MouseButton::Left, x, y) => {
+                let rows = children.iter().flat_map(|stack| stack.children.iter());
+                for (nth, row) in rows.enumerate() {
+                    if row.view.rect().contains(x, y) {
+                        self.select(nth);
+                        return true;
+                    }
+                }
+                false
+            }
+            Event::KeyPress(KeyCode::Up, _) | Event::KeyPress(KeyCode::Left, _) => {
+                self.select(selected.saturating_sub(1));
+                true
+            }
+            Event::KeyPress(KeyCode::Down, _) | Event::KeyPress(KeyCode::Right, _) => {
+                self.select(selected + 1);
+                true
+            }
+            Event::KeyPress(KeyCode::Home, _) => {
+                self.select(0);
+                true
+            }
+            Event::KeyPress(KeyCode::End, _) => {
+                self.select(self.options.len().saturating_sub(1));
+                true
+            }
+            _ => false,
+        }
+    }
+}
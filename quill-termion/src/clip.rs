@@ -0,0 +1,123 @@
+// Clipping of rendered output. Views draw with cursor moves, SGR styling and
+// plain text; the text is filtered cell by cell against the clip rect, and
+// everything else is passed through.
+
+use quill::Rect;
+use std::io::Write;
+use termion::cursor;
+use unicode_width::UnicodeWidthChar;
+
+/// Write `output` to `screen`, dropping the characters that fall outside
+/// `clip`. A wide character is dropped unless it fits entirely.
+pub fn write_clipped(output: &str, clip: Rect, screen: &mut impl Write) {
+    // Terminal cursor, 1-based like `cursor::Goto`.
+    let (mut x, mut y) = (1.0, 1.0);
+    // Whether the terminal cursor lags behind `(x, y)` because characters
+    // were dropped.
+    let mut moved = false;
+    let mut chars = output.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        if c == '\x1b' {
+            let mut end = start + 1;
+            if chars.next_if(|&(_, c)| c == '[').is_some() {
+                // Parameters up to the final byte of the control sequence.
+                for (at, c) in chars.by_ref() {
+                    end = at + c.len_utf8();
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            } else if let Some((at, c)) = chars.next() {
+                end = at + c.len_utf8();
+            }
+            let sequence = &output[start..end];
+            match goto_target(sequence) {
+                Some((col, row)) => {
+                    (x, y) = (col, row);
+                    moved = true;
+                }
+                None => screen.write_all(sequence.as_bytes()).unwrap(),
+            }
+            continue;
+        }
+        let width = c.width().unwrap_or(0) as f64;
+        let fits = clip.contains(x, y) && (width <= 1.0 || clip.contains(x + width - 1.0, y));
+        if fits {
+            if moved {
+                write!(screen, "{}", cursor::Goto(x as u16, y as u16)).unwrap();
+                moved = false;
+            }
+            write!(screen, "{}", c).unwrap();
+        } else {
+            moved = true;
+        }
+        x += width;
+    }
+}
+
+/// Column and row of a `cursor::Goto` sequence.
+fn goto_target(sequence: &str) -> Option<(f64, f64)> {
+    let params = sequence.strip_prefix("\x1b[")?.strip_suffix('H')?;
+    let (row, col) = params.split_once(';')?;
+    Some((col.parse().ok()?, row.parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Offsettable, Paddable, TermText};
+    use quill::*;
+
+    fn clipped(output: &str, clip: Rect) -> String {
+        let mut screen = Vec::new();
+        write_clipped(output, clip, &mut screen);
+        String::from_utf8(screen).unwrap()
+    }
+
+    #[test]
+    fn text_outside_the_clip_is_dropped() {
+        let clip = Rect::new(Offset::new(8.0, 3.0), Size::new(4.0, 1.0));
+        assert_eq!(clipped("\x1b[3;6Habcdefgh", clip), "\x1b[3;8Hcdef");
+        assert_eq!(clipped("\x1b[4;8Habcd", clip), "");
+        // Styling is kept even when its text is dropped.
+        assert_eq!(clipped("\x1b[1mab\x1b[0m", clip), "\x1b[1m\x1b[0m");
+    }
+
+    #[test]
+    fn wide_characters_fit_entirely_or_not_at_all() {
+        let clip = Rect::new(Offset::new(1.0, 1.0), Size::new(2.0, 1.0));
+        assert_eq!(clipped("\x1b[1;1Ha你b", clip), "\x1b[1;1Ha");
+        assert_eq!(clipped("\x1b[1;1H你b", clip), "\x1b[1;1H你");
+    }
+
+    #[test]
+    fn clips_narrow_down_the_tree() {
+        // The inner padding is pushed half out of the outer one.
+        let view = TermText::new("abcdefgh")
+            .padding(1.0)
+            .offset(5.0, 0.0)
+            .padding(1.0);
+        let tree = ViewTree::new(view.any_view());
+        tree.layout(Constraint::new(12.0, 5.0));
+        tree.set_offset(Offset::new(1.0, 1.0));
+        let outer = Rect::new(Offset::new(2.0, 2.0), Size::new(10.0, 3.0));
+
+        let offsetted = &tree.children[0];
+        assert_eq!(offsetted.view.clip.get(), Some(outer));
+        // `Offsetted` doesn't clip, so its child gets the outer clip as is.
+        let inner = &offsetted.children[0];
+        assert_eq!(inner.view.rect().left(), 7.0);
+        assert_eq!(inner.view.clip.get(), Some(outer));
+        // The text is clipped by both paddings.
+        let text = &inner.children[0];
+        assert_eq!(text.view.rect().left(), 8.0);
+        assert_eq!(
+            text.view.clip.get(),
+            Some(Rect::new(Offset::new(8.0, 3.0), Size::new(4.0, 1.0)))
+        );
+        assert_eq!(
+            text.view.visible_rect(),
+            Some(Rect::new(Offset::new(8.0, 3.0), Size::new(4.0, 1.0)))
+        );
+    }
+}
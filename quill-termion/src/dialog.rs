@@ -0,0 +1,202 @@
+use crate::button::Button;
+use crate::TermText;
+use quill::*;
+
+// Columns between two buttons.
+const GAP: f64 = 2.0;
+
+/// A message with a row of buttons under it, meant to be shown in a `Modal`.
+/// The first button gets the focus when the modal opens, so put the safe
+/// choice first.
+#[derive(Debug, Clone)]
+pub struct Dialog {
+    pub message: String,
+    pub buttons: Vec<Button>,
+}
+
+impl Dialog {
+    pub fn new(message: impl ToString) -> Self {
+        Dialog {
+            message: message.to_string(),
+            buttons: Vec::new(),
+        }
+    }
+
+    pub fn button(mut self, label: impl ToString, cb: impl Fn() + 'static) -> Self {
+        self.buttons.push(Button::new(label, cb));
+        self
+    }
+
+    fn lines(&self) -> usize {
+        self.message.lines().count()
+    }
+}
+
+impl Hydrate for Dialog {
+    fn hydrate(&mut self, other: &Self) {
+        self.message = other.message.clone();
+        self.buttons = other.buttons.clone();
+    }
+}
+
+impl View for Dialog {
+    fn children(&self) -> Vec<AnyView> {
+        let lines = self
+            .message
+            .lines()
+            .map(|line| TermText::new(line).any_view());
+        let buttons = self.buttons.iter().map(|button| button.clone().any_view());
+        lines.chain(buttons).collect()
+    }
+
+    fn layout(&self, children: &[ViewTree], constraint: Constraint) -> Size {
+        for child in children {
+            child.layout(constraint);
+        }
+        let (lines, buttons) = children.split_at(self.lines().min(children.len()));
+        // One column of padding on each side, and a blank line above the
+        // buttons.
+        Size {
+            width: inner_width(lines, buttons) + 2.0,
+            height: lines.len() as f64 + if buttons.is_empty() { 0.0 } else { 2.0 },
+        }
+    }
+
+    fn intrinsic(&self, children: &[ViewTree], kind: Intrinsic, extent: f64) -> f64 {
+        let (lines, buttons) = children.split_at(self.lines().min(children.len()));
+        if kind.is_width() {
+            let width = |child: &ViewTree| child.intrinsic(kind, extent);
+            let row = buttons.iter().map(width).sum::<f64>()
+                + GAP * buttons.len().saturating_sub(1) as f64;
+            lines.iter().map(width).fold(row, f64::max) + 2.0
+        } else {
+            lines.len() as f64 + if buttons.is_empty() { 0.0 } else { 2.0 }
+        }
+    }
+
+    fn set_offset(&self, children: &[ViewTree], offset: Offset) {
+        let (lines, buttons) = children.split_at(self.lines().min(children.len()));
+        for (nth, line) in lines.iter().enumerate() {
+            line.set_offset(Offset {
+                x: offset.x + 1.0,
+                y: offset.y + nth as f64,
+            });
+        }
+        // Buttons are right-aligned.
+        let mut x = offset.x + 1.0 + inner_width(lines, buttons) - row_width(buttons);
+        let y = offset.y + lines.len() as f64 + 1.0;
+        for button in buttons {
+            button.set_offset(Offset { x, y });
+            x += button.view.size.get().width + GAP;
+        }
+    }
+}
+
+fn row_width(buttons: &[ViewTree]) -> f64 {
+    let widths: f64 = buttons
+        .iter()
+        .map(|button| button.view.size.get().width)
+        .sum();
+    widths + GAP * buttons.len().saturating_sub(1) as f64
+}
+
+fn inner_width(lines: &[ViewTree], buttons: &[ViewTree]) -> f64 {
+    lines
+        .iter()
+        .map(|line| line.view.size.get().width)
+        .fold(row_width(buttons), f64::max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Session;
+    use quill_derive::Hydrate;
+
+    #[derive(Debug, Clone, PartialEq, Hydrate)]
+    struct App {
+        open: State<bool>,
+        log: State<Vec<&'static str>>,
+    }
+
+    impl View for App {
+        fn body(&self) -> AnyView {
+            let log = self.log.clone();
+            let push = move |entry| {
+                let log = log.clone();
+                move || log.update(|log| log.push(entry))
+            };
+            let open = self.open.clone();
+            let dialog = self.open.get().then(|| {
+                Modal::new(
+                    Dialog::new("Quit?")
+                        .button("Cancel", push("cancel"))
+                        .button("Quit", push("quit")),
+                )
+                .on_dismiss(move || open.set(false))
+            });
+            Overlay::new(Button::new("behind", push("behind")))
+                .layer(dialog)
+                .any_view()
+        }
+    }
+
+    fn session() -> (Session<App>, App) {
+        let app = App {
+            open: State::new(false),
+            log: State::new(Vec::new()),
+        };
+        let mut session = Session::new(app.clone());
+        session.frame(0.0, Size::new(40.0, 10.0));
+        (session, app)
+    }
+
+    fn key(key: KeyCode) -> Event {
+        Event::KeyPress(key, Modifiers::default())
+    }
+
+    fn focused(session: &Session<App>) -> Option<String> {
+        let view = session.focus.current()?;
+        Some(view.downcast_ref::<Button>().unwrap().label.clone())
+    }
+
+    #[test]
+    fn focus_is_trapped_until_escape() {
+        let (mut session, app) = session();
+        session.event(key(KeyCode::Tab));
+        assert_eq!(focused(&session).as_deref(), Some("behind"));
+
+        app.open.set(true);
+        session.frame(0.1, Size::new(40.0, 10.0));
+        assert_eq!(focused(&session).as_deref(), Some("Cancel"));
+        session.event(key(KeyCode::Tab));
+        assert_eq!(focused(&session).as_deref(), Some("Quit"));
+        session.event(key(KeyCode::Tab));
+        assert_eq!(focused(&session).as_deref(), Some("Cancel"));
+
+        session.event(key(KeyCode::Esc));
+        assert!(!app.open.get());
+        session.frame(0.2, Size::new(40.0, 10.0));
+        assert!(session.tree.modal().is_none());
+        assert_eq!(focused(&session).as_deref(), Some("behind"));
+        assert!(app.log.get().is_empty());
+    }
+
+    #[test]
+    fn clicks_outside_are_blocked() {
+        let (mut session, app) = session();
+        app.open.set(true);
+        session.frame(0.1, Size::new(40.0, 10.0));
+        // The button behind the dialog, at the top left.
+        session.event(Event::MousePress(MouseButton::Left, 2.0, 1.0));
+        session.event(Event::MouseRelease(2.0, 1.0));
+        assert!(app.log.get().is_empty());
+        assert!(app.open.get());
+
+        // The click took the focus away; Tab brings it back to the dialog.
+        assert_eq!(focused(&session), None);
+        session.event(key(KeyCode::Tab));
+        session.event(key(KeyCode::Enter));
+        assert_eq!(app.log.get(), ["cancel"]);
+    }
+}
@@ -0,0 +1,361 @@
+// The view-tree inspector: a pane next to the UI listing the views, with the
+// details of the selected one. The selected view is outlined in the UI, and
+// its `State` fields can be edited in place.
+
+use crate::bounds::outline;
+use crate::clip::write_clipped;
+use crate::style::{Color, Style};
+use crate::text::{slice_columns, str_width};
+use quill::*;
+use std::any::TypeId;
+use std::collections::HashSet;
+use std::io::Write;
+
+// A visible line of the tree.
+struct Row<'a> {
+    // Child indices from the root.
+    path: Vec<usize>,
+    depth: usize,
+    tree: &'a ViewTree,
+}
+
+/// Keys: the arrows move through the tree and fold it, Space folds, Tab
+/// picks a `State` of the selected view and Enter edits it. Other keys are
+/// left to the UI.
+#[derive(Debug, Default)]
+pub struct Inspector {
+    // Child indices from the root to the selected view.
+    selected: Vec<usize>,
+    // Views whose children are hidden.
+    collapsed: HashSet<Vec<usize>>,
+    // First row shown.
+    scroll: usize,
+    // The selected `State` of the selected view.
+    field: usize,
+    // The text typed for the selected `State` while editing it.
+    editing: Option<String>,
+    // Why the last edit failed.
+    error: Option<String>,
+    // Refuse edits, e.g. while recording, as a replay would miss them.
+    read_only: bool,
+}
+
+impl Inspector {
+    pub fn new() -> Inspector {
+        Inspector::default()
+    }
+
+    /// Show the states without letting them be edited.
+    pub fn read_only(mut self) -> Self {
+        self.read_only = true;
+        self
+    }
+
+    /// The visible rows; the children of `EmptyView`s and of collapsed
+    /// views are left out.
+    fn rows<'a>(&self, tree: &'a ViewTree) -> Vec<Row<'a>> {
+        fn visit<'a>(
+            inspector: &Inspector,
+            tree: &'a ViewTree,
+            path: &mut Vec<usize>,
+            rows: &mut Vec<Row<'a>>,
+        ) {
+            rows.push(Row {
+                path: path.clone(),
+                depth: path.len(),
+                tree,
+            });
+            if inspector.collapsed.contains(path) {
+                return;
+            }
+            for (nth, child) in tree.children.iter().enumerate() {
+                if child.view.view_type() != TypeId::of::<EmptyView>() {
+                    path.push(nth);
+                    visit(inspector, child, path, rows);
+                    path.pop();
+                }
+            }
+        }
+        let mut rows = Vec::new();
+        visit(self, tree, &mut Vec::new(), &mut rows);
+        rows
+    }
+
+    /// The row of the selected view. When it is gone or hidden, its closest
+    /// visible ancestor is selected instead.
+    fn current(&mut self, rows: &[Row]) -> usize {
+        loop {
+            if let Some(nth) = rows.iter().position(|row| row.path == self.selected) {
+                return nth;
+            }
+            self.selected.pop();
+        }
+    }
+
+    fn select(&mut self, row: &Row) {
+        if row.path != self.selected {
+            self.selected = row.path.clone();
+            self.field = 0;
+            self.editing = None;
+            self.error = None;
+        }
+    }
+
+    /// Handle a key press. All keys are taken while the inspector is shown.
+    /// Handle a key press. Returns `false` for keys the inspector does not
+    /// use, so they can go to the UI.
+    pub fn key(&mut self, tree: &ViewTree, code: KeyCode) -> bool {
+        let rows = self.rows(tree);
+        let nth = self.current(&rows);
+        let row = &rows[nth];
+        let states = row.tree.view.borrow().states().len();
+        if let Some(text) = &mut self.editing {
+            match code {
+                KeyCode::Char(c) => text.push(c),
+                KeyCode::Backspace => {
+                    text.pop();
+                }
+                KeyCode::Enter => {
+                    let view = row.tree.view.borrow();
+                    let result = match view.states().get(self.field) {
+                        Some((_, state)) => state.edit(text),
+                        None => Ok(()),
+                    };
+                    self.error = result.err();
+                    self.editing = None;
+                }
+                KeyCode::Esc => self.editing = None,
+                _ => (),
+            }
+            return true;
+        }
+        let used = matches!(
+            code,
+            KeyCode::Up
+                | KeyCode::Down
+                | KeyCode::PageUp
+                | KeyCode::PageDown
+                | KeyCode::Home
+                | KeyCode::End
+                | KeyCode::Left
+                | KeyCode::Right
+                | KeyCode::Char(' ')
+                | KeyCode::Tab
+                | KeyCode::BackTab
+                | KeyCode::Enter
+        );
+        if !used {
+            return false;
+        }
+        let has_children = rows.get(nth + 1).is_some_and(|next| next.depth > row.depth)
+            || self.collapsed.contains(&row.path);
+        match code {
+            KeyCode::Up if nth > 0 => self.select(&rows[nth - 1]),
+            KeyCode::Down if nth + 1 < rows.len() => self.select(&rows[nth + 1]),
+            KeyCode::PageUp => self.select(&rows[nth.saturating_sub(10)]),
+            KeyCode::PageDown => self.select(&rows[(nth + 10).min(rows.len() - 1)]),
+            KeyCode::Home => self.select(&rows[0]),
+            KeyCode::End => self.select(&rows[rows.len() - 1]),
+            KeyCode::Left => {
+                if has_children && !self.collapsed.contains(&row.path) {
+                    self.collapsed.insert(row.path.clone());
+                } else if let Some(parent) = rows[..nth]
+                    .iter()
+                    .rev()
+                    .find(|parent| parent.depth < row.depth)
+                {
+                    self.select(parent);
+                }
+            }
+            KeyCode::Right | KeyCode::Char(' ') if self.collapsed.contains(&row.path) => {
+                self.collapsed.remove(&row.path);
+            }
+            KeyCode::Right if has_children => self.select(&rows[nth + 1]),
+            KeyCode::Char(' ') if has_children => {
+                self.collapsed.insert(row.path.clone());
+            }
+            KeyCode::Tab if states > 0 => self.field = (self.field + 1) % states,
+            KeyCode::BackTab if states > 0 => self.field = (self.field + states - 1) % states,
+            KeyCode::Enter if self.read_only => {
+                self.error = Some("states can't be edited while recording".to_string());
+            }
+            KeyCode::Enter => {
+                let view = row.tree.view.borrow();
+                if let Some((_, state)) = view.states().get(self.field) {
+                    self.editing = Some(state.debug());
+                    self.error = None;
+                }
+            }
+            _ => (),
+        }
+        true
+    }
+
+    /// Draw the pane in `pane` and outline the selected view, clipped to
+    /// `ui`.
+    pub fn render(&mut self, tree: &ViewTree, ui: Rect, pane: Rect, out: &mut impl Write) {
+        let rows = self.rows(tree);
+        let nth = self.current(&rows);
+        let selected = rows[nth].tree;
+
+        let mut highlight = Vec::new();
+        outline(
+            selected.view.rect(),
+            Style::new().fg(Color::Yellow).bold(),
+            true,
+            &mut highlight,
+        );
+        write_clipped(&String::from_utf8_lossy(&highlight), ui, out);
+
+        let (x, top) = (pane.left() as u16, pane.top() as u16);
+        let width = (pane.size.width as usize).saturating_sub(2);
+        let height = pane.size.height as usize;
+        for y in 0..height {
+            write!(
+                out,
+                "{}{}│",
+                termion::cursor::Goto(x, top + y as u16),
+                Style::new().dim()
+            )
+            .unwrap();
+        }
+        let mut line = |y: usize, text: &str, style: Style| {
+            if y < height {
+                let text = slice_columns(text, 0, width);
+                let pad = width - str_width(&text);
+                write!(
+                    out,
+                    "{}{}{}{}{}",
+                    termion::cursor::Goto(x + 2, top + y as u16),
+                    style,
+                    text,
+                    " ".repeat(pad),
+                    termion::style::Reset
+                )
+                .unwrap();
+            }
+        };
+
+        // The tree takes the upper half, below a title.
+        let tree_height = (height / 2).saturating_sub(1).max(1);
+        if nth < self.scroll {
+            self.scroll = nth;
+        } else if nth >= self.scroll + tree_height {
+            self.scroll = nth + 1 - tree_height;
+        }
+        line(
+            0,
+            "Views  ↑↓ move  ←→ fold  Tab/Enter edit",
+            Style::new().bold(),
+        );
+        for (y, row) in rows.iter().skip(self.scroll).take(tree_height).enumerate() {
+            let folded = self.collapsed.contains(&row.path);
+            let leaf = !folded
+                && !row
+                    .tree
+                    .children
+                    .iter()
+                    .any(|child| child.view.view_type() != TypeId::of::<EmptyView>());
+            let marker = match (leaf, folded) {
+                (true, _) => "  ",
+                (false, true) => "▸ ",
+                (false, false) => "▾ ",
+            };
+            let size = row.tree.view.size.get();
+            let text = format!(
+                "{}{}{} {}×{}",
+                "  ".repeat(row.depth),
+                marker,
+                row.tree.view.short_type_name(),
+                size.width,
+                size.height
+            );
+            let style = if row.path == self.selected {
+                Style::new().reverse()
+            } else {
+                Style::new()
+            };
+            line(1 + y, &text, style);
+        }
+
+        let view = &selected.view;
+        let (size, offset) = (view.size.get(), view.offset.get());
+        let dirty = if view.borrow().is_dirty() {
+            "yes"
+        } else {
+            "no"
+        };
+        let mut details = vec![
+            (format!("Type    {}", view.type_name()), Style::new()),
+            (format!("Key     {}", view.key().0), Style::new()),
+            (
+                format!("Size    {}×{}", size.width, size.height),
+                Style::new(),
+            ),
+            (format!("Offset  {}, {}", offset.x, offset.y), Style::new()),
+            (format!("Dirty   {}", dirty), Style::new()),
+        ];
+        let profile = view.profile();
+        let ms = |time: std::time::Duration| time.as_secs_f64() * 1000.0;
+        details.push((
+            format!(
+                "Counts  {} bodies, {} hydrations, {} skipped, {} layouts, {} measures",
+                profile.bodies,
+                profile.hydrations,
+                profile.skipped,
+                profile.layouts,
+                profile.measures
+            ),
+            Style::new(),
+        ));
+        details.push((
+            format!(
+                "Time    body {:.3} ms, hydrate {:.3} ms, layout {:.3} ms, measure {:.3} ms",
+                ms(profile.body_time),
+                ms(profile.hydrate_time),
+                ms(profile.layout_time),
+                ms(profile.measure_time)
+            ),
+            Style::new(),
+        ));
+        let states: Vec<_> = view
+            .borrow()
+            .states()
+            .into_iter()
+            .map(|(name, state)| (name, state.debug()))
+            .collect();
+        for (nth, (name, value)) in states.into_iter().enumerate() {
+            if nth == 0 {
+                details.push(("States".to_string(), Style::new().bold()));
+            }
+            let current = nth == self.field;
+            let text = match &self.editing {
+                Some(text) if current => format!("> {} = {}▏", name, text),
+                _ if current => format!("> {} = {}", name, value),
+                _ => format!("  {} = {}", name, value),
+            };
+            let style = if current && self.editing.is_some() {
+                Style::new().underline()
+            } else {
+                Style::new()
+            };
+            details.push((text, style));
+        }
+        if let Some(error) = &self.error {
+            details.push((error.clone(), Style::new().fg(Color::Red)));
+        }
+        details.push(("Debug".to_string(), Style::new().bold()));
+        let debug = format!("{:#?}", view.borrow());
+        details.extend(
+            debug
+                .lines()
+                .map(|text| (text.to_string(), Style::new().dim())),
+        );
+
+        let first = tree_height + 1;
+        line(first, &"─".repeat(width), Style::new().dim());
+        for (y, (text, style)) in details.iter().enumerate() {
+            line(first + 1 + y, text, *style);
+        }
+    }
+}
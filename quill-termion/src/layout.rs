@@ -0,0 +1,510 @@
+use quill::*;
+use quill_derive::*;
+
+/// The size a `Frame` accepts on one axis. Unset bounds follow the child.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct FrameSize {
+    pub min: Option<f64>,
+    /// The size taken, as far as the parent allows, unless `max` makes the
+    /// frame grow. It is also the size measured, which `.fixed_size()` then
+    /// proposes.
+    pub ideal: Option<f64>,
+    pub max: Option<f64>,
+}
+
+impl FrameSize {
+    pub fn fixed(size: f64) -> Self {
+        FrameSize {
+            min: Some(size),
+            ideal: Some(size),
+            max: Some(size),
+        }
+    }
+
+    /// The largest size to offer the child out of `available`.
+    fn propose(&self, available: f64) -> f64 {
+        let available = if available.is_finite() {
+            available
+        } else {
+            self.ideal.unwrap_or(available)
+        };
+        available
+            .min(self.max.unwrap_or(f64::INFINITY))
+            .max(self.min.unwrap_or(0.0))
+    }
+
+    /// The size of the frame around a child of size `child`, out of
+    /// `available`.
+    fn resolve(&self, child: f64, available: f64) -> f64 {
+        let mut size = child;
+        match (self.max, self.ideal) {
+            // With a maximum, the frame grows to fill the space.
+            (Some(max), _) if available.is_finite() => size = size.max(available.min(max)),
+            (_, Some(ideal)) => size = ideal.min(available),
+            _ => (),
+        }
+        if let Some(max) = self.max {
+            size = size.min(max);
+        }
+        size.max(self.min.unwrap_or(0.0))
+    }
+}
+
+/// Gives its child a size of its own choosing, and places the child inside it
+/// with `alignment`. Each axis has a minimum, ideal and maximum size; see
+/// `Frameable::frame` and the builder methods.
+#[derive(Debug, Clone)]
+pub struct Frame {
+    pub child: AnyView,
+    pub width: FrameSize,
+    pub height: FrameSize,
+    pub alignment: Alignment,
+    // The size from the last layout, to align the child in.
+    size: State<Size>,
+}
+
+pub trait Frameable: View + Sized {
+    /// A frame of exactly `width` x `height`. An axis left as `None` takes the
+    /// size of the child.
+    fn frame(
+        self,
+        width: impl Into<Option<f64>>,
+        height: impl Into<Option<f64>>,
+        alignment: Alignment,
+    ) -> Frame {
+        Frame::new(self, width, height, alignment)
+    }
+}
+impl<X: View> Frameable for X {}
+
+impl Frame {
+    pub fn new(
+        child: impl View,
+        width: impl Into<Option<f64>>,
+        height: impl Into<Option<f64>>,
+        alignment: Alignment,
+    ) -> Self {
+        Frame {
+            child: child.any_view(),
+            width: width
+                .into()
+                .map_or_else(FrameSize::default, FrameSize::fixed),
+            height: height
+                .into()
+                .map_or_else(FrameSize::default, FrameSize::fixed),
+            alignment,
+            size: State::new(Size::zero()),
+        }
+    }
+
+    pub fn min_width(mut self, width: f64) -> Self {
+        self.width.min = Some(width);
+        self
+    }
+
+    pub fn ideal_width(mut self, width: f64) -> Self {
+        self.width.ideal = Some(width);
+        self
+    }
+
+    /// Grow up to `width` when the parent allows it. `f64::INFINITY` fills
+    /// whatever is proposed.
+    pub fn max_width(mut self, width: f64) -> Self {
+        self.width.max = Some(width);
+        self
+    }
+
+    pub fn min_height(mut self, height: f64) -> Self {
+        self.height.min = Some(height);
+        self
+    }
+
+    pub fn ideal_height(mut self, height: f64) -> Self {
+        self.height.ideal = Some(height);
+        self
+    }
+
+    /// Grow up to `height` when the parent allows it. `f64::INFINITY` fills
+    /// whatever is proposed.
+    pub fn max_height(mut self, height: f64) -> Self {
+        self.height.max = Some(height);
+        self
+    }
+}
+
+impl Hydrate for Frame {
+    fn hydrate(&mut self, other: &Self) {
+        self.child = other.child.clone();
+        self.width = other.width;
+        self.height = other.height;
+        self.alignment = other.alignment;
+    }
+}
+
+impl View for Frame {
+    fn body(&self) -> AnyView {
+        self.child.clone()
+    }
+
+    fn layout(&self, children: &[ViewTree], constraint: Constraint) -> Size {
+        let proposed = Size::new(
+            self.width.propose(constraint.max_width()),
+            self.height.propose(constraint.max_height()),
+        );
+        let mut child = Size::zero();
+        for tree in children {
+            tree.layout(Constraint::loose(proposed));
+            child = tree.view.size.get();
+        }
+        let size = constraint.constrain(Size::new(
+            self.width.resolve(child.width, constraint.max_width()),
+            self.height.resolve(child.height, constraint.max_height()),
+        ));
+        if size != self.size.get() {
+            self.size.set(size);
+        }
+        size
+    }
+
+    fn intrinsic(&self, children: &[ViewTree], kind: Intrinsic, extent: f64) -> f64 {
+        let (along, across) = if kind.is_width() {
+            (self.width, self.height)
+        } else {
+            (self.height, self.width)
+        };
+        if let Some(size) = along.min.filter(|min| Some(*min) == along.max) {
+            return size;
+        }
+        if let (Intrinsic::MaxWidth | Intrinsic::MaxHeight, Some(ideal)) = (kind, along.ideal) {
+            return ideal;
+        }
+        child_intrinsic(children, kind, across.propose(extent))
+            .min(along.max.unwrap_or(f64::INFINITY))
+            .max(along.min.unwrap_or(0.0))
+    }
+
+    fn set_offset(&self, children: &[ViewTree], offset: Offset) {
+        align_children(children, offset, self.alignment, self.size.get());
+    }
+}
+
+/// Places its child within all the space the parent proposes, instead of
+/// taking the size of the child. On an unbounded axis it takes the size of
+/// the child.
+#[derive(Debug, Clone)]
+pub struct Aligned {
+    pub child: AnyView,
+    pub alignment: Alignment,
+    // The size from the last layout, to align the child in.
+    size: State<Size>,
+}
+
+pub trait Alignable: View + Sized {
+    fn align(self, alignment: Alignment) -> Aligned {
+        Aligned::new(self, alignment)
+    }
+}
+impl<X: View> Alignable for X {}
+
+impl Aligned {
+    pub fn new(child: impl View, alignment: Alignment) -> Self {
+        Aligned {
+            child: child.any_view(),
+            alignment,
+            size: State::new(Size::zero()),
+        }
+    }
+}
+
+impl Hydrate for Aligned {
+    fn hydrate(&mut self, other: &Self) {
+        self.child = other.child.clone();
+        self.alignment = other.alignment;
+    }
+}
+
+impl View for Aligned {
+    fn body(&self) -> AnyView {
+        self.child.clone()
+    }
+
+    fn layout(&self, children: &[ViewTree], constraint: Constraint) -> Size {
+        let mut child = Size::zero();
+        for tree in children {
+            tree.layout(constraint.loosen());
+            child = tree.view.size.get();
+        }
+        let fill = |available: f64, child: f64| {
+            if available.is_finite() {
+                available.max(child)
+            } else {
+                child
+            }
+        };
+        let size = constraint.constrain(Size::new(
+            fill(constraint.max_width(), child.width),
+            fill(constraint.max_height(), child.height),
+        ));
+        if size != self.size.get() {
+            self.size.set(size);
+        }
+        size
+    }
+
+    fn set_offset(&self, children: &[ViewTree], offset: Offset) {
+        align_children(children, offset, self.alignment, self.size.get());
+    }
+}
+
+fn child_intrinsic(children: &[ViewTree], kind: Intrinsic, extent: f64) -> f64 {
+    children
+        .first()
+        .map_or(0.0, |child| child.intrinsic(kind, extent))
+}
+
+fn align_children(children: &[ViewTree], offset: Offset, alignment: Alignment, size: Size) {
+    for child in children {
+        let at = alignment.position(child.view.size.get(), size);
+        child.set_offset(offset.translate(at.x, at.y));
+    }
+}
+
+/// Draws its child moved by `x`, `y` cells. Layout is not affected, so the
+/// child can end up over its neighbours; it is only clipped by the ancestors
+/// of this view.
+#[derive(Debug, Clone, PartialEq, Hydrate)]
+pub struct Offsetted {
+    pub child: AnyView,
+    pub x: f64,
+    pub y: f64,
+}
+
+pub trait Offsettable: View + Sized {
+    fn offset(self, x: f64, y: f64) -> Offsetted {
+        Offsetted::new(self, x, y)
+    }
+}
+impl<X: View> Offsettable for X {}
+
+impl Offsetted {
+    pub fn new(child: impl View, x: f64, y: f64) -> Self {
+        Offsetted {
+            child: child.any_view(),
+            x,
+            y,
+        }
+    }
+}
+
+impl View for Offsetted {
+    fn body(&self) -> AnyView {
+        self.child.clone()
+    }
+
+    fn clip_children(&self, _bounds: Rect) -> Option<Rect> {
+        None
+    }
+
+    fn set_offset(&self, children: &[ViewTree], offset: Offset) {
+        for child in children {
+            child.set_offset(offset.translate(self.x, self.y));
+        }
+    }
+}
+
+/// Lays out its child without the parent's limits on the chosen axes, so the
+/// child takes its ideal size there even if it does not fit. A child that
+/// fills whatever it is given, and so has no ideal size, gets the parent's
+/// limit instead.
+#[derive(Debug, Clone, PartialEq, Hydrate)]
+pub struct FixedSize {
+    pub child: AnyView,
+    pub horizontal: bool,
+    pub vertical: bool,
+}
+
+pub trait FixedSizable: View + Sized {
+    fn fixed_size(self) -> FixedSize {
+        FixedSize::new(self, true, true)
+    }
+
+    fn fixed_size_axes(self, horizontal: bool, vertical: bool) -> FixedSize {
+        FixedSize::new(self, horizontal, vertical)
+    }
+}
+impl<X: View> FixedSizable for X {}
+
+impl FixedSize {
+    pub fn new(child: impl View, horizontal: bool, vertical: bool) -> Self {
+        FixedSize {
+            child: child.any_view(),
+            horizontal,
+            vertical,
+        }
+    }
+}
+
+impl View for FixedSize {
+    fn body(&self) -> AnyView {
+        self.child.clone()
+    }
+
+    fn layout(&self, children: &[ViewTree], constraint: Constraint) -> Size {
+        let mut size = Size::zero();
+        for child in children {
+            let mut max = constraint.max_size();
+            if self.horizontal {
+                let height = if self.vertical {
+                    f64::INFINITY
+                } else {
+                    max.height
+                };
+                max.width = bounded(child.intrinsic(Intrinsic::MaxWidth, height), max.width);
+            }
+            if self.vertical {
+                max.height = bounded(child.intrinsic(Intrinsic::MaxHeight, max.width), max.height);
+            }
+            child.layout(Constraint::loose(max));
+            size = child.view.size.get();
+        }
+        size
+    }
+
+    // On a fixed axis the child always gets its ideal size.
+    fn intrinsic(&self, children: &[ViewTree], kind: Intrinsic, extent: f64) -> f64 {
+        let kind = match kind {
+            Intrinsic::MinWidth if self.horizontal => Intrinsic::MaxWidth,
+            Intrinsic::MinHeight if self.vertical => Intrinsic::MaxHeight,
+            kind => kind,
+        };
+        let unbounded = if kind.is_width() {
+            self.vertical
+        } else {
+            self.horizontal
+        };
+        let extent = if unbounded { f64::INFINITY } else { extent };
+        child_intrinsic(children, kind, extent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Paddable, TermText, TextField};
+
+    /// Lay `view` out in `width` x `height` at the top left of the screen.
+    fn laid_out(view: impl View, width: f64, height: f64) -> ViewTree {
+        let tree = ViewTree::new(view.any_view());
+        tree.layout(Constraint::new(width, height));
+        tree.set_offset(Offset::new(1.0, 1.0));
+        tree
+    }
+
+    fn child(tree: &ViewTree) -> Rect {
+        tree.children[0].view.rect()
+    }
+
+    #[test]
+    fn frame_aligns_the_child_in_a_fixed_size() {
+        let tree = laid_out(
+            TermText::new("ab").frame(6.0, 3.0, Alignment::BOTTOM_TRAILING),
+            20.0,
+            10.0,
+        );
+        assert_eq!(tree.view.size.get(), Size::new(6.0, 3.0));
+        assert_eq!(
+            child(&tree),
+            Rect::new(Offset::new(5.0, 3.0), Size::new(2.0, 1.0))
+        );
+        // The parent's limits win.
+        let tree = laid_out(
+            TermText::new("ab").frame(6.0, 3.0, Alignment::CENTER),
+            4.0,
+            2.0,
+        );
+        assert_eq!(tree.view.size.get(), Size::new(4.0, 2.0));
+    }
+
+    #[test]
+    fn frame_bounds() {
+        let frame = TermText::new("ab")
+            .frame(None, None, Alignment::CENTER)
+            .min_width(5.0)
+            .max_height(3.0);
+        let tree = laid_out(frame, 20.0, 10.0);
+        assert_eq!(tree.view.size.get(), Size::new(5.0, 3.0));
+
+        let frame = TermText::new("ab")
+            .frame(None, None, Alignment::TRAILING)
+            .max_width(f64::INFINITY);
+        let tree = laid_out(frame, 20.0, 10.0);
+        assert_eq!(tree.view.size.get(), Size::new(20.0, 1.0));
+        assert_eq!(child(&tree).origin, Offset::new(19.0, 1.0));
+    }
+
+    #[test]
+    fn frame_takes_its_ideal_size() {
+        let frame = || {
+            TermText::new("ab")
+                .frame(None, None, Alignment::LEADING)
+                .ideal_width(7.0)
+        };
+        assert_eq!(laid_out(frame(), 20.0, 10.0).view.size.get().width, 7.0);
+        assert_eq!(laid_out(frame(), 4.0, 10.0).view.size.get().width, 4.0);
+        assert_eq!(
+            laid_out(frame().fixed_size(), 4.0, 10.0)
+                .view
+                .size
+                .get()
+                .width,
+            7.0
+        );
+    }
+
+    #[test]
+    fn aligned_fills_the_space() {
+        let tree = laid_out(
+            TermText::new("ab").align(Alignment::BOTTOM_TRAILING),
+            10.0,
+            4.0,
+        );
+        assert_eq!(tree.view.size.get(), Size::new(10.0, 4.0));
+        assert_eq!(child(&tree).origin, Offset::new(9.0, 4.0));
+    }
+
+    #[test]
+    fn offset_moves_without_layout() {
+        let tree = laid_out(TermText::new("ab").offset(3.0, 1.0), 10.0, 4.0);
+        assert_eq!(tree.view.size.get(), Size::new(2.0, 1.0));
+        assert_eq!(child(&tree).origin, Offset::new(4.0, 2.0));
+    }
+
+    #[test]
+    fn fixed_size_keeps_the_ideal_size() {
+        let text = "longer than the space";
+        let tree = laid_out(TermText::new(text).fixed_size(), 5.0, 1.0);
+        assert_eq!(tree.view.size.get().width, text.len() as f64);
+        let tree = laid_out(TermText::new(text).fixed_size_axes(false, true), 5.0, 1.0);
+        assert_eq!(tree.children[0].view.constraint.get().max_width(), 5.0);
+    }
+
+    #[test]
+    fn fixed_size_offers_a_bounded_size_to_fill_views() {
+        let field = TextField::new(State::new(String::from("hi")));
+        let stack = Stack::new().with_child(field.clone().fixed_size());
+        let tree = laid_out(stack, 80.0, 24.0);
+        let view = &tree.children[0].children[0].view;
+        let size = view.size.get();
+        assert!(size.width.is_finite());
+        assert!(view.constraint.get().max_width().is_finite());
+        field.render(size, view.offset.get(), &mut Vec::new());
+    }
+
+    #[test]
+    fn padding_insets_the_child() {
+        let padding = TermText::new("ab").padding(EdgeInsets::symmetric(1.0, 2.0));
+        let tree = laid_out(padding, 20.0, 10.0);
+        assert_eq!(tree.view.size.get(), Size::new(6.0, 3.0));
+        assert_eq!(child(&tree).origin, Offset::new(3.0, 2.0));
+    }
+}
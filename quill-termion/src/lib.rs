@@ -1,8 +1,6 @@
 use quill::*;
 use quill_derive::*;
-use std::cell::Cell;
 use std::io::{stdin, stdout, Write};
-use std::rc::Rc;
 use std::sync::mpsc::sync_channel;
 use std::{thread, time};
 use termion::input::{MouseTerminal, TermRead};
@@ -11,25 +9,79 @@ use termion::screen::*;
 use termion::terminal_size;
 use termion::{clear, cursor};
 
+mod bounds;
+mod button;
+mod chart;
+mod checkbox;
+mod clip;
+mod dialog;
+mod inspector;
+mod layout;
+mod list;
+mod progress;
+mod record;
+mod style;
+mod table;
+mod tabs;
+mod text;
+mod text_area;
+mod text_field;
+mod tree_view;
+
+pub use button::*;
+pub use chart::*;
+pub use checkbox::*;
+pub use dialog::*;
+pub use layout::*;
+pub use list::*;
+pub use progress::*;
+pub use record::*;
+pub use style::*;
+pub use table::*;
+pub use tabs::*;
+pub use text_area::*;
+pub use text_field::*;
+pub use tree_view::*;
+
 #[derive(Debug, Clone, PartialEq, Hydrate)]
 pub struct TermText {
     pub text: String,
+    pub style: Style,
 }
 
 impl TermText {
     pub fn new(text: impl ToString) -> TermText {
         TermText {
             text: text.to_string(),
+            style: Style::default(),
         }
     }
+
+    pub fn styled(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
     pub fn render(&self, offset: Offset, screen: &mut impl std::io::Write) {
-        write!(
-            screen,
-            "{}{}",
-            cursor::Goto(offset.x as u16, offset.y as u16),
-            self.text
-        )
-        .unwrap();
+        if self.style.is_plain() {
+            write!(
+                screen,
+                "{}{}",
+                cursor::Goto(offset.x as u16, offset.y as u16),
+                self.text
+            )
+            .unwrap();
+        } else {
+            write!(
+                screen,
+                "{}{}{}{}",
+                cursor::Goto(offset.x as u16, offset.y as u16),
+                self.style,
+                self.text,
+                termion::style::Reset
+            )
+            .unwrap();
+        }
     }
 }
 
@@ -43,52 +95,19 @@ impl View for TermText {
 
     fn layout(&self, _children: &[ViewTree], _constraint: Constraint) -> Size {
         Size {
-            width: self.text.len() as f64,
+            width: text::str_width(&self.text) as f64,
             height: 1.0,
         }
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct Stats {
-    body: Rc<Cell<u32>>,
-}
-
-impl Stats {
-    pub fn new() -> Stats {
-        Stats {
-            body: Rc::new(Cell::new(0)),
-        }
-    }
-}
-
-impl Default for Stats {
-    #[track_caller]
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-impl Hydrate for Stats {
-    fn hydrate(&mut self, _other: &Self) {}
-    fn is_same(&self, _other: &Self) -> bool {
-        true
-    }
-    fn is_dirty(&self) -> bool {
-        false
-    }
-}
-
-impl View for Stats {
-    fn body(&self) -> AnyView {
-        self.body.set(self.body.get() + 1);
-        TermText::new(format!("Body {}", self.body.get())).any_view()
-    }
-}
-
-#[derive(Clone)]
+// Children are left out of `is_same`, so that any view can be wrapped; they
+// are compared by their own hydration.
+#[derive(Clone, Hydrate)]
 pub struct OnClick<T: View> {
+    #[hydrate(ignore_eq)]
     pub cb: std::rc::Rc<dyn Fn()>,
+    #[hydrate(ignore_eq)]
     pub child: T,
 }
 impl<T: View> std::fmt::Debug for OnClick<T> {
@@ -110,13 +129,6 @@ impl<T: View> OnClick<T> {
     }
 }
 
-impl<T: View + Clone> Hydrate for OnClick<T> {
-    fn hydrate(&mut self, other: &Self) {
-        self.cb = other.cb.clone();
-        self.child = other.child.clone();
-    }
-}
-
 impl<T: View + Clone> View for OnClick<T> {
     fn body(&self) -> AnyView {
         self.child.clone().any_view()
@@ -137,24 +149,24 @@ impl<T: View + Clone> View for OnClick<T> {
             Size::zero()
         }
     }
-    fn event(&self, size: Size, offset: Offset, _children: &[ViewTree], event: &Event) {
+    fn event(&self, size: Size, offset: Offset, _children: &[ViewTree], event: &Event) -> bool {
         match event {
             Event::MousePress(_btn, x, y) => {
                 // dbg!(x, y, size, offset);
-                if *x >= offset.x
-                    && *x <= offset.x + size.width - 1.0
-                    && *y >= offset.y
-                    && *y <= offset.y + size.height - 1.0
-                {
+                if Rect::new(offset, size).contains(*x, *y) {
                     (*self.cb)();
+                    return true;
                 }
+                false
             }
+            _ => false,
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Hydrate)]
 pub struct Box<T: View> {
+    #[hydrate(ignore_eq)]
     pub child: T,
 }
 
@@ -173,57 +185,56 @@ impl<T: View> Box<T> {
     pub fn render(_screen: impl std::io::Write) {}
 }
 
-impl<T: View + Clone> Hydrate for Box<T> {
-    fn hydrate(&mut self, other: &Self) {
-        self.child = other.child.clone();
-    }
-}
-
 impl<T: View + Clone> View for Box<T> {
     fn body(&self) -> AnyView {
         self.child.clone().any_view()
     }
-    fn layout(&self, children: &[ViewTree], mut constraint: Constraint) -> Size {
+    fn layout(&self, children: &[ViewTree], constraint: Constraint) -> Size {
         if let [child] = children {
-            constraint.max_height -= 2.;
-            constraint.max_width -= 2.;
-            child.layout(constraint);
-            let mut size = child.view.size.get();
-            size.width += 2.0;
-            size.height += 2.0;
-            size
+            let border = EdgeInsets::all(1.0);
+            child.layout(constraint.deflate(border));
+            constraint.constrain(border.inflate(child.view.size.get()))
         } else {
             // eprintln!("Can't decide widget size: {:?}", self);
             Size::zero()
         }
     }
 
-    fn set_offset(&self, children: &[ViewTree], mut offset: Offset) {
-        offset.x += 1.0;
-        offset.y += 1.0;
+    fn intrinsic(&self, children: &[ViewTree], kind: Intrinsic, extent: f64) -> f64 {
+        inset_intrinsic(children, kind, extent, EdgeInsets::all(1.0))
+    }
+
+    // Children stay inside the border.
+    fn clip_children(&self, bounds: Rect) -> Option<Rect> {
+        Some(bounds.inset(EdgeInsets::all(1.0)))
+    }
+
+    fn set_offset(&self, children: &[ViewTree], offset: Offset) {
         for child in children {
-            child.set_offset(offset)
+            child.set_offset(offset.translate(1.0, 1.0))
         }
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Hydrate)]
 pub struct Padding {
-    pub padding: f64,
+    pub insets: EdgeInsets,
     pub child: AnyView,
 }
 
 pub trait Paddable: View + Sized {
-    fn padding(self, padding: f64) -> Padding {
-        Padding::new(self, padding)
+    /// Pad every side by the same amount with `.padding(1.0)`, or each side
+    /// separately with `.padding(EdgeInsets::symmetric(0.0, 2.0))`.
+    fn padding(self, insets: impl Into<EdgeInsets>) -> Padding {
+        Padding::new(self, insets)
     }
 }
 impl<X: View> Paddable for X {}
 
 impl Padding {
-    pub fn new(child: impl View, padding: f64) -> Self {
+    pub fn new(child: impl View, insets: impl Into<EdgeInsets>) -> Self {
         Padding {
-            padding,
+            insets: insets.into(),
             child: child.any_view(),
         }
     }
@@ -235,52 +246,371 @@ impl View for Padding {
     fn body(&self) -> AnyView {
         self.child.clone()
     }
-    fn layout(&self, children: &[ViewTree], mut constraint: Constraint) -> Size {
+    fn layout(&self, children: &[ViewTree], constraint: Constraint) -> Size {
         if let [child] = children {
-            constraint.max_height -= self.padding * 2.0;
-            constraint.max_width -= self.padding * 2.0;
-            child.layout(constraint);
-            let mut size = child.view.size.get();
-            size.width += self.padding * 2.0;
-            size.height += self.padding * 2.0;
-            size
+            child.layout(constraint.deflate(self.insets));
+            constraint.constrain(self.insets.inflate(child.view.size.get()))
         } else {
             // eprintln!("Can't decide widget size: {:?}", self);
             Size::zero()
         }
     }
 
-    fn set_offset(&self, children: &[ViewTree], mut offset: Offset) {
-        offset.x += self.padding;
-        offset.y += self.padding;
+    fn intrinsic(&self, children: &[ViewTree], kind: Intrinsic, extent: f64) -> f64 {
+        inset_intrinsic(children, kind, extent, self.insets)
+    }
+
+    fn clip_children(&self, bounds: Rect) -> Option<Rect> {
+        Some(bounds.inset(self.insets))
+    }
+
+    fn set_offset(&self, children: &[ViewTree], offset: Offset) {
+        let offset = offset.translate(self.insets.left, self.insets.top);
         for child in children {
             child.set_offset(offset)
         }
     }
 }
 
+/// An intrinsic size of the only child, grown by `insets`.
+fn inset_intrinsic(children: &[ViewTree], kind: Intrinsic, extent: f64, insets: EdgeInsets) -> f64 {
+    let (across, along) = if kind.is_width() {
+        (insets.vertical(), insets.horizontal())
+    } else {
+        (insets.horizontal(), insets.vertical())
+    };
+    children.first().map_or(0.0, |child| {
+        child.intrinsic(kind, (extent - across).max(0.0))
+    }) + along
+}
+
 enum Mode {
     UI,
-    Tree,
+    /// The UI with the layout rectangles outlined.
+    Bounds,
+    /// The UI next to the view-tree inspector.
+    Inspector,
 }
 
 impl Mode {
-    fn toggle(&mut self) {
+    fn next(&mut self) {
         match self {
-            Mode::UI => *self = Mode::Tree,
-            Mode::Tree => *self = Mode::UI,
+            Mode::UI => *self = Mode::Bounds,
+            Mode::Bounds => *self = Mode::Inspector,
+            Mode::Inspector => *self = Mode::UI,
         }
     }
 }
 
+fn translate(evt: &termion::event::Event) -> Option<quill::Event> {
+    use termion::event as tui;
+    let none = Modifiers::none();
+    let shift = Modifiers {
+        shift: true,
+        ..none
+    };
+    let ctrl = Modifiers { ctrl: true, ..none };
+    let alt = Modifiers { alt: true, ..none };
+    let key = |code, mods| Some(quill::Event::KeyPress(code, mods));
+    match *evt {
+        tui::Event::Key(k) => match k {
+            tui::Key::Char('\n') => key(KeyCode::Enter, none),
+            tui::Key::Char('\t') => key(KeyCode::Tab, none),
+            tui::Key::Char(c) => key(KeyCode::Char(c), none),
+            tui::Key::Ctrl(c) => key(KeyCode::Char(c), ctrl),
+            tui::Key::Alt(c) => key(KeyCode::Char(c), alt),
+            tui::Key::Backspace => key(KeyCode::Backspace, none),
+            tui::Key::Delete => key(KeyCode::Delete, none),
+            tui::Key::BackTab => key(KeyCode::BackTab, shift),
+            tui::Key::Esc => key(KeyCode::Esc, none),
+            tui::Key::Left => key(KeyCode::Left, none),
+            tui::Key::ShiftLeft => key(KeyCode::Left, shift),
+            tui::Key::CtrlLeft => key(KeyCode::Left, ctrl),
+            tui::Key::AltLeft => key(KeyCode::Left, alt),
+            tui::Key::Right => key(KeyCode::Right, none),
+            tui::Key::ShiftRight => key(KeyCode::Right, shift),
+            tui::Key::CtrlRight => key(KeyCode::Right, ctrl),
+            tui::Key::AltRight => key(KeyCode::Right, alt),
+            tui::Key::Up => key(KeyCode::Up, none),
+            tui::Key::ShiftUp => key(KeyCode::Up, shift),
+            tui::Key::CtrlUp => key(KeyCode::Up, ctrl),
+            tui::Key::AltUp => key(KeyCode::Up, alt),
+            tui::Key::Down => key(KeyCode::Down, none),
+            tui::Key::ShiftDown => key(KeyCode::Down, shift),
+            tui::Key::CtrlDown => key(KeyCode::Down, ctrl),
+            tui::Key::AltDown => key(KeyCode::Down, alt),
+            tui::Key::Home => key(KeyCode::Home, none),
+            tui::Key::CtrlHome => key(KeyCode::Home, ctrl),
+            tui::Key::End => key(KeyCode::End, none),
+            tui::Key::CtrlEnd => key(KeyCode::End, ctrl),
+            tui::Key::PageUp => key(KeyCode::PageUp, none),
+            tui::Key::PageDown => key(KeyCode::PageDown, none),
+            tui::Key::Insert => key(KeyCode::Insert, none),
+            tui::Key::F(n) => key(KeyCode::F(n), none),
+            _ => None,
+        },
+        tui::Event::Mouse(m) => match m {
+            tui::MouseEvent::Press(btn, x, y) => {
+                let btn = match btn {
+                    tui::MouseButton::Left => MouseButton::Left,
+                    tui::MouseButton::Right => MouseButton::Right,
+                    tui::MouseButton::Middle => MouseButton::Middle,
+                    tui::MouseButton::WheelUp => MouseButton::WheelUp,
+                    tui::MouseButton::WheelDown => MouseButton::WheelDown,
+                    _ => return None,
+                };
+                Some(quill::Event::MousePress(btn, x as f64, y as f64))
+            }
+            tui::MouseEvent::Release(x, y) => Some(quill::Event::MouseRelease(x as f64, y as f64)),
+            tui::MouseEvent::Hold(x, y) => Some(quill::Event::MouseHold(x as f64, y as f64)),
+        },
+        tui::Event::Unsupported(ref bytes) => {
+            parse_motion(bytes).or_else(|| parse_modified_key(bytes))
+        }
+    }
+}
+
+// Termion doesn't decode motion without a pressed button, which is reported
+// in SGR mode as `ESC [ < 35 ; x ; y M` (plus modifier bits).
+fn parse_motion(bytes: &[u8]) -> Option<quill::Event> {
+    let body = std::str::from_utf8(bytes)
+        .ok()?
+        .strip_prefix("\x1b[<")?
+        .strip_suffix('M')?;
+    let mut nums = body.split(';').map(|n| n.parse::<u16>().ok());
+    let (cb, x, y) = (nums.next()??, nums.next()??, nums.next()??);
+    if cb & 32 != 0 && cb & 3 == 3 {
+        Some(quill::Event::MouseMove(x as f64, y as f64))
+    } else {
+        None
+    }
+}
+
+// Termion gives up on special keys with modifiers, such as Ctrl-PgUp, which
+// is sent as `ESC [ 5 ; 5 ~`. The second number is 1 plus the modifier bits.
+fn parse_modified_key(bytes: &[u8]) -> Option<quill::Event> {
+    let body = std::str::from_utf8(bytes)
+        .ok()?
+        .strip_prefix("\x1b[")?
+        .strip_suffix('~')?;
+    let (code, mods) = body.split_once(';')?;
+    let code = match code.parse::<u8>().ok()? {
+        1 | 7 => KeyCode::Home,
+        2 => KeyCode::Insert,
+        3 => KeyCode::Delete,
+        4 | 8 => KeyCode::End,
+        5 => KeyCode::PageUp,
+        6 => KeyCode::PageDown,
+        _ => return None,
+    };
+    let bits = mods.parse::<u8>().ok()?.checked_sub(1)?;
+    let mods = Modifiers {
+        shift: bits & 1 != 0,
+        alt: bits & 2 != 0,
+        ctrl: bits & 4 != 0,
+    };
+    Some(quill::Event::KeyPress(code, mods))
+}
+
+// Report pointer motion even when no button is held, for hover effects.
+const ENTER_MOTION_SEQUENCE: &str = "\x1b[?1003h";
+const EXIT_MOTION_SEQUENCE: &str = "\x1b[?1003l";
+
+/// Draw a single view. Returns where the terminal cursor should be placed
+/// if the view wants to show it.
+fn render_view(view: &AnyView, screen: &mut impl std::io::Write) -> Option<Offset> {
+    if let Some(text) = view.downcast_ref::<TermText>() {
+        text.render(view.offset.get(), screen);
+    } else if let Some(modal) = view.downcast_ref::<Modal>() {
+        render_frame(
+            view.size.get(),
+            view.offset.get(),
+            modal.title.as_deref(),
+            screen,
+        );
+    } else if let Some(field) = view.downcast_ref::<TextField>() {
+        return field.render(view.size.get(), view.offset.get(), screen);
+    } else if let Some(area) = view.downcast_ref::<TextArea>() {
+        return area.render(view.size.get(), view.offset.get(), screen);
+    } else if let Some(list) = view.downcast_ref::<List>() {
+        list.render(view.size.get(), view.offset.get(), screen);
+    } else if let Some(chart) = view.downcast_ref::<Sparkline>() {
+        chart.render(view.size.get(), view.offset.get(), screen);
+    } else if let Some(chart) = view.downcast_ref::<BarChart>() {
+        chart.render(view.size.get(), view.offset.get(), screen);
+    } else if let Some(chart) = view.downcast_ref::<LineChart>() {
+        chart.render(view.size.get(), view.offset.get(), screen);
+    } else if let Some(bar) = view.downcast_ref::<ProgressBar>() {
+        bar.render(view.size.get(), view.offset.get(), screen);
+    } else if let Some(spinner) = view.downcast_ref::<Spinner>() {
+        spinner.render(view.size.get(), view.offset.get(), screen);
+    } else if let Some(tabs) = view.downcast_ref::<Tabs>() {
+        tabs.render(view.size.get(), view.offset.get(), screen);
+    } else if let Some(tree) = view.downcast_ref::<TreeView>() {
+        tree.render(view.size.get(), view.offset.get(), screen);
+    }
+    None
+}
+
+/// Draw a single view, clipped to `AnyView::clip`. A caret outside the
+/// clip is hidden.
+fn render_clipped(view: &AnyView, screen: &mut impl std::io::Write) -> Option<Offset> {
+    let Some(clip) = view.clip.get() else {
+        return render_view(view, screen);
+    };
+    let mut output = Vec::new();
+    let caret = render_view(view, &mut output);
+    clip::write_clipped(&String::from_utf8_lossy(&output), clip, screen);
+    caret.filter(|at| clip.contains(at.x, at.y))
+}
+
+/// Draw a box over the area, blanking what was drawn below it.
+fn render_frame(size: Size, offset: Offset, title: Option<&str>, screen: &mut impl std::io::Write) {
+    let width = size.width as usize;
+    let height = size.height as usize;
+    if width < 2 || height < 2 {
+        return;
+    }
+    let title = title.map_or(String::new(), |title| format!(" {} ", title));
+    let title = text::slice_columns(&title, 0, width - 2);
+    let rule = "─".repeat(width - 2 - text::str_width(&title));
+    let goto = |line: usize| cursor::Goto(offset.x as u16, offset.y as u16 + line as u16);
+    write!(screen, "{}┌{}{}┐", goto(0), title, rule).unwrap();
+    for line in 1..height - 1 {
+        write!(screen, "{}│{}│", goto(line), " ".repeat(width - 2)).unwrap();
+    }
+    write!(screen, "{}└{}┘", goto(height - 1), "─".repeat(width - 2)).unwrap();
+}
+
+/// The app with its view tree and focus, driven by `run` and `replay`.
+struct Session<A> {
+    app: A,
+    tree: ViewTree,
+    focus: Focus,
+}
+
+impl<A: View + Clone> Session<A> {
+    fn new(app: A) -> Self {
+        let tree = ViewTree::new(app.clone().any_view());
+        Session {
+            app,
+            tree,
+            focus: Focus::new(),
+        }
+    }
+
+    /// Deliver an input event. Returns `false` when the app should quit.
+    fn event(&mut self, event: quill::Event) -> bool {
+        let Session { tree, focus, .. } = self;
+        // While a modal is open, input is limited to it.
+        let modal = tree.modal();
+        let scope = modal.unwrap_or(tree);
+        if let quill::Event::MousePress(_, x, y) = event {
+            focus.set(scope.focusable_at(x, y));
+        }
+        // Keys go to the focused view, mouse events to everyone.
+        let handled = match (event, focus.current(), modal) {
+            (quill::Event::KeyPress(..), Some(view), _) => scope.dispatch(view, &event),
+            (quill::Event::KeyPress(..), None, Some(modal)) => modal.dispatch(&modal.view, &event),
+            (quill::Event::KeyPress(..), None, None) => false,
+            _ => scope.event(&event),
+        };
+        if !handled {
+            match event {
+                quill::Event::KeyPress(KeyCode::Char('q'), mods) if mods.is_empty() => {
+                    return false
+                }
+                quill::Event::KeyPress(KeyCode::Tab, _) => focus.next(scope),
+                quill::Event::KeyPress(KeyCode::BackTab, _) => focus.prev(scope),
+                _ => (),
+            }
+        }
+        true
+    }
+
+    /// Hydrate the app and lay it out in `size`, `time` seconds after the
+    /// start.
+    fn frame(&mut self, time: f64, size: Size) {
+        let Session { app, tree, focus } = self;
+        tree.event(&quill::Event::Frame(time));
+        tree.perform_hydrate(app.clone().any_view());
+        tree.layout(Constraint::new(size.width, size.height));
+        if quill::take_rebuild() {
+            tree.perform_hydrate(app.clone().any_view());
+            tree.layout(Constraint::new(size.width, size.height));
+        }
+        tree.set_offset(Offset::new(1.0, 1.0));
+        tree.clean();
+        focus.sync(tree);
+        focus.trap(tree);
+    }
+
+    /// Draw every view. Returns where the caret goes, if a view shows it.
+    fn render(&self, screen: &mut impl std::io::Write) -> Option<Offset> {
+        let mut caret = None;
+        for view in self.tree.flatten() {
+            caret = render_clipped(&view, screen).or(caret);
+        }
+        caret
+    }
+}
+
+/// Show `caret`, or hide the terminal cursor.
+fn place_caret(caret: Option<Offset>, screen: &mut impl std::io::Write) -> std::io::Result<()> {
+    match caret {
+        Some(at) => write!(
+            screen,
+            "{}{}",
+            cursor::Goto(at.x as u16, at.y as u16),
+            cursor::Show
+        ),
+        None => write!(screen, "{}", cursor::Hide),
+    }
+}
+
+/// Run the app in the terminal until it quits or Ctrl-C is pressed. F12
+/// cycles through the debug modes.
+///
+/// Keys go to the focused view first. Tab and Shift-Tab that it leaves
+/// unhandled move the focus, and an unhandled `q` quits, so views that take
+/// text input keep these keys.
+///
+/// When the `QUILL_RECORD` environment variable names a file, the input is
+/// recorded to it for `replay`.
 pub fn run(app: impl View + Clone) -> std::io::Result<()> {
+    let recorder = match std::env::var_os("QUILL_RECORD") {
+        Some(path) => Some(Recorder::create(path)?),
+        None => None,
+    };
+    run_session(Session::new(app), recorder)
+}
+
+/// Like `run`, recording the input to `path` for `replay`.
+pub fn run_recording(
+    app: impl View + Clone,
+    path: impl AsRef<std::path::Path>,
+) -> std::io::Result<()> {
+    run_session(Session::new(app), Some(Recorder::create(path)?))
+}
+
+fn run_session<A: View + Clone>(
+    mut session: Session<A>,
+    mut recorder: Option<Recorder>,
+) -> std::io::Result<()> {
     let stdin = stdin();
-    let mut screen = MouseTerminal::from(AlternateScreen::from(stdout().into_raw_mode()?));
+    let mut screen = MouseTerminal::from(stdout().into_raw_mode()?.into_alternate_screen()?);
     // let mut screen = MouseTerminal::from(stdout().into_raw_mode()?);
     let mut mode = Mode::UI;
+    let mut inspector = match recorder {
+        Some(_) => inspector::Inspector::new().read_only(),
+        None => inspector::Inspector::new(),
+    };
+    // The layout the outlines were last drawn for.
+    let mut outlined: Vec<Rect> = Vec::new();
     write!(
         screen,
-        "{}{}{}",
+        "{}{}{}{}",
+        ENTER_MOTION_SEQUENCE,
         clear::All,
         cursor::Goto(1, 1),
         cursor::Hide,
@@ -300,7 +630,7 @@ pub fn run(app: impl View + Clone) -> std::io::Result<()> {
         }
     });
 
-    let mut tree = ViewTree::new(app.clone().any_view());
+    let start = time::Instant::now();
 
     'outer: loop {
         use termion::event as tui;
@@ -312,56 +642,82 @@ pub fn run(app: impl View + Clone) -> std::io::Result<()> {
                 screen,
                 "{}{}{}",
                 clear::All,
-                cursor::Goto(1 + size.0 - evt_str.len() as u16, 1),
+                cursor::Goto((1 + size.0).saturating_sub(evt_str.len() as u16).max(1), 1),
                 evt_str
             )?;
             match evt {
-                tui::Event::Key(tui::Key::Char('q')) => break 'outer,
-                tui::Event::Key(tui::Key::Char('\t')) => {
+                tui::Event::Key(tui::Key::Ctrl('c')) => break 'outer,
+                // Cycle through the UI, layout bounds and inspector modes.
+                tui::Event::Key(tui::Key::F(12)) => {
                     write!(screen, "{}", clear::All)?;
-                    mode.toggle()
+                    mode.next()
                 }
                 // Event::Key(Key::Char('1')) => write!(screen, "{}", ToMainScreen)?,
                 // Event::Key(Key::Char('2')) => write!(screen, "{}", ToAlternateScreen)?,
-                tui::Event::Mouse(tui::MouseEvent::Press(_btn, x, y)) => tree.event(
-                    &quill::Event::MousePress(MouseButton::Left, x as f64, y as f64),
-                ),
-                _ => (),
+                evt => {
+                    // The inspector passes on the keys it does not use.
+                    let event = translate(&evt).filter(|event| match (&mode, event) {
+                        (Mode::Inspector, quill::Event::KeyPress(code, _)) => {
+                            !inspector.key(&session.tree, *code)
+                        }
+                        _ => true,
+                    });
+                    if let Some(event) = event {
+                        if !session.event(event) {
+                            break 'outer;
+                        }
+                        if let Some(recorder) = &mut recorder {
+                            recorder.event(event);
+                        }
+                    }
+                }
             }
             screen.flush()?;
         }
 
-        tree.perform_hydrate(app.clone().any_view());
         let size = terminal_size()?;
-        tree.layout(Constraint {
-            min_width: 0.0,
-            max_width: size.0 as f64,
-            min_height: 0.0,
-            max_height: size.1 as f64,
-        });
-        tree.set_offset(Offset { x: 1., y: 1. });
-        tree.clean();
-
-        match mode {
-            Mode::UI => {
-                for view in tree.flatten() {
-                    if let Some(text) = view.downcast_ref::<TermText>() {
-                        text.render(view.offset.get(), &mut screen);
-                    }
-                }
-                screen.flush()?;
+        let screen_size = Size::new(size.0 as f64, size.1 as f64);
+        // The inspector takes a third of the screen on the right.
+        let pane_width = match mode {
+            Mode::Inspector => (screen_size.width / 3.0)
+                .floor()
+                .max(30.0)
+                .min(screen_size.width),
+            _ => 0.0,
+        };
+        let ui = Rect::new(
+            Offset::new(1.0, 1.0),
+            Size::new(screen_size.width - pane_width, screen_size.height),
+        );
+        let time = start.elapsed().as_secs_f64();
+        if let Some(recorder) = &mut recorder {
+            recorder.frame(time, ui.size)?;
+        }
+        session.frame(time, ui.size);
+
+        if let Mode::Bounds | Mode::Inspector = mode {
+            // Outlines move with the layout, so start afresh when it changed.
+            let layout: Vec<Rect> = session.tree.flatten().iter().map(AnyView::rect).collect();
+            if layout != outlined {
+                write!(screen, "{}", clear::All)?;
+                outlined = layout;
             }
-            Mode::Tree => {
-                write!(screen, "{}{}UI Tree:", clear::All, cursor::Goto(1, 1))?;
-                let mut vec = Vec::new();
-                ptree::write_tree(&tree.tree(), &mut vec)?;
-                let output = std::str::from_utf8(&vec).unwrap();
-                for (nth, line) in output.lines().enumerate() {
-                    write!(screen, "{}{}", cursor::Goto(1, 1 + nth as u16), line)?;
-                }
-                screen.flush()?;
+        }
+        let caret = session.render(&mut screen);
+        match mode {
+            Mode::Bounds => bounds::render_bounds(&session.tree, ui, &mut screen),
+            Mode::Inspector => {
+                let pane = Rect::new(
+                    Offset::new(ui.right(), 1.0),
+                    Size::new(pane_width, screen_size.height),
+                );
+                inspector.render(&session.tree, ui, pane, &mut screen);
             }
+            Mode::UI => (),
         }
+        // Placed last, as the outlines and the inspector move the cursor.
+        place_caret(caret, &mut screen)?;
+        screen.flush()?;
 
         // tree.pretty_print();
 
@@ -369,7 +725,7 @@ pub fn run(app: impl View + Clone) -> std::io::Result<()> {
     }
 
     write!(screen, "{}", ToMainScreen).unwrap();
-    write!(screen, "{}", cursor::Show)?;
+    write!(screen, "{}{}", EXIT_MOTION_SEQUENCE, cursor::Show)?;
     screen.flush()?;
 
     // thread::sleep(time::Duration::from_secs(10));
@@ -0,0 +1,274 @@
+use crate::style::Style;
+use crate::text::{slice_columns, str_width};
+use quill::*;
+use std::collections::BTreeSet;
+use std::rc::Rc;
+use termion::cursor;
+
+// Seconds between the presses of a double-click, on the frame clock.
+const DOUBLE_CLICK: f64 = 0.4;
+
+/// A scrollable list of rows with a selected row, bound to the index of the
+/// selection. In multi-select mode rows can also be marked with Space,
+/// Shift-Up/Down or a click.
+#[derive(Clone)]
+pub struct List {
+    pub items: Vec<String>,
+    pub selected: State<usize>,
+    pub marked: Option<State<BTreeSet<usize>>>,
+    pub height: Option<f64>,
+    pub highlight: Style,
+    pub on_activate: Option<Rc<dyn Fn(usize)>>,
+    scroll: State<usize>,
+    follow: State<bool>,
+    last_click: State<Option<(usize, f64)>>,
+    focused: State<bool>,
+}
+
+impl std::fmt::Debug for List {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("List")
+            .field("items", &self.items.len())
+            .field("selected", &self.selected)
+            .field("marked", &self.marked)
+            .field("scroll", &self.scroll)
+            .field("focused", &self.focused)
+            .finish()
+    }
+}
+
+impl List {
+    pub fn new(selected: State<usize>) -> Self {
+        List {
+            items: Vec::new(),
+            selected,
+            marked: None,
+            height: None,
+            highlight: Style::new().reverse(),
+            on_activate: None,
+            scroll: State::new(0),
+            follow: State::new(true),
+            last_click: State::new(None),
+            focused: State::new(false),
+        }
+    }
+
+    pub fn with_item(mut self, item: impl ToString) -> Self {
+        self.items.push(item.to_string());
+        self
+    }
+
+    pub fn with_items<I: ToString>(mut self, items: impl IntoIterator<Item = I>) -> Self {
+        self.items
+            .extend(items.into_iter().map(|item| item.to_string()));
+        self
+    }
+
+    /// Enable multi-select mode, keeping the marked rows in `marked`.
+    pub fn multi_select(mut self, marked: State<BTreeSet<usize>>) -> Self {
+        self.marked = Some(marked);
+        self
+    }
+
+    pub fn height(mut self, height: f64) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    pub fn highlight(mut self, style: Style) -> Self {
+        self.highlight = style;
+        self
+    }
+
+    /// Called with the row index on Enter or double-click.
+    pub fn on_activate(mut self, cb: impl Fn(usize) + 'static) -> Self {
+        self.on_activate = Some(Rc::new(cb));
+        self
+    }
+
+    fn last(&self) -> usize {
+        self.items.len().saturating_sub(1)
+    }
+
+    fn select(&self, nth: usize, extend: bool) {
+        let nth = nth.min(self.last());
+        let prev = self.selected.get().min(self.last());
+        if extend {
+            if let Some(marked) = &self.marked {
+                marked.update(|marked| marked.extend(prev.min(nth)..=prev.max(nth)));
+            }
+        }
+        self.selected.set(nth);
+        self.follow.set(true);
+    }
+
+    fn toggle_mark(&self, nth: usize) {
+        if let Some(marked) = &self.marked {
+            marked.update(|marked| {
+                if !marked.remove(&nth) {
+                    marked.insert(nth);
+                }
+            });
+        }
+    }
+
+    fn activate(&self, nth: usize) -> bool {
+        match &self.on_activate {
+            Some(cb) if nth < self.items.len() => {
+                cb(nth);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn key_press(&self, size: Size, key: KeyCode, mods: Modifiers) -> bool {
+        let selected = self.selected.get();
+        let page = (size.height as usize).max(1);
+        let extend = mods.shift && self.marked.is_some();
+        match key {
+            KeyCode::Up => self.select(selected.saturating_sub(1), extend),
+            KeyCode::Down => self.select(selected + 1, extend),
+            KeyCode::PageUp if !mods.ctrl => self.select(selected.saturating_sub(page), extend),
+            KeyCode::PageDown if !mods.ctrl => self.select(selected + page, extend),
+            KeyCode::Home => self.select(0, extend),
+            KeyCode::End => self.select(self.last(), extend),
+            KeyCode::Char(' ') if self.marked.is_some() => self.toggle_mark(selected),
+            KeyCode::Char('a') if mods.ctrl => match &self.marked {
+                Some(marked) => marked.set((0..self.items.len()).collect()),
+                None => return false,
+            },
+            KeyCode::Enter => return self.activate(selected),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Draw the visible rows, scrolling to keep the selection in view.
+    pub fn render(&self, size: Size, offset: Offset, screen: &mut impl std::io::Write) {
+        let width = size.width.max(0.0) as usize;
+        let height = size.height.max(0.0) as usize;
+        let selected = self.selected.get().min(self.last());
+        let mut scroll = self.scroll.get();
+        if self.follow.get() {
+            if selected < scroll {
+                scroll = selected;
+            } else if selected >= scroll + height {
+                scroll = selected + 1 - height;
+            }
+            self.follow.set(false);
+        }
+        scroll = scroll.min(self.items.len().saturating_sub(height));
+        if scroll != self.scroll.get() {
+            self.scroll.set(scroll);
+        }
+
+        let marked = self.marked.as_ref().map(|marked| marked.get());
+        for line in 0..height {
+            let nth = scroll + line;
+            let mut row = match (&marked, self.items.get(nth)) {
+                (Some(marked), Some(item)) => {
+                    let mark = if marked.contains(&nth) {
+                        "[x] "
+                    } else {
+                        "[ ] "
+                    };
+                    format!("{}{}", mark, item)
+                }
+                (None, Some(item)) => item.clone(),
+                (_, None) => String::new(),
+            };
+            row = slice_columns(&row, 0, width);
+            let pad = width.saturating_sub(str_width(&row));
+            let style = if nth == selected && nth < self.items.len() {
+                if self.focused.get() {
+                    self.highlight
+                } else {
+                    Style::new().bold()
+                }
+            } else {
+                Style::new()
+            };
+            write!(
+                screen,
+                "{}{}{}{}{}",
+                cursor::Goto(offset.x as u16, offset.y as u16 + line as u16),
+                style,
+                row,
+                " ".repeat(pad),
+                termion::style::Reset
+            )
+            .unwrap();
+        }
+    }
+}
+
+impl Hydrate for List {
+    fn hydrate(&mut self, other: &Self) {
+        self.items = other.items.clone();
+        self.selected = other.selected.clone();
+        self.marked = other.marked.clone();
+        self.height = other.height;
+        self.highlight = other.highlight;
+        self.on_activate = other.on_activate.clone();
+    }
+}
+
+impl View for List {
+    fn children(&self) -> Vec<AnyView> {
+        Vec::new()
+    }
+
+    fn layout(&self, _children: &[ViewTree], constraint: Constraint) -> Size {
+        let mark = if self.marked.is_some() { 4 } else { 0 };
+        let widest = self.items.iter().map(|item| str_width(item)).max();
+        constraint.constrain(Size {
+            width: (widest.unwrap_or(0) + mark) as f64,
+            height: self
+                .height
+                .unwrap_or_else(|| (self.items.len() as f64).min(constraint.max_height())),
+        })
+    }
+
+    fn focusable(&self) -> bool {
+        !self.items.is_empty()
+    }
+
+    fn set_focus(&self, focused: bool) {
+        self.focused.set(focused);
+    }
+
+    fn event(&self, size: Size, offset: Offset, _children: &[ViewTree], event: &Event) -> bool {
+        let bounds = Rect::new(offset, size);
+        match *event {
+            Event::MousePress(MouseButton::Left, x, y) if bounds.contains(x, y) => {
+                let nth = self.scroll.get() + (y - offset.y) as usize;
+                if nth >= self.items.len() {
+                    return true;
+                }
+                self.select(nth, false);
+                let now = quill::frame_time();
+                let double = matches!(self.last_click.get(),
+                    Some((prev, at)) if prev == nth && now - at < DOUBLE_CLICK);
+                if double {
+                    self.last_click.set(None);
+                    self.activate(nth);
+                } else {
+                    self.toggle_mark(nth);
+                    self.last_click.set(Some((nth, now)));
+                }
+                true
+            }
+            Event::MousePress(MouseButton::WheelUp, x, y) if bounds.contains(x, y) => {
+                self.scroll.set(self.scroll.get().saturating_sub(3));
+                true
+            }
+            Event::MousePress(MouseButton::WheelDown, x, y) if bounds.contains(x, y) => {
+                self.scroll.set(self.scroll.get() + 3);
+                true
+            }
+            Event::KeyPress(key, mods) => self.key_press(size, key, mods),
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,302 @@
+use crate::style::{Color, Style};
+use crate::text::{columns, slice_columns, str_width};
+use quill::*;
+use termion::cursor;
+
+// Left-aligned blocks from one to seven eighths of a cell.
+const EIGHTHS: [char; 7] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+// Seconds for the indeterminate bar to travel across and back.
+const BOUNCE_PERIOD: f64 = 2.0;
+
+// Width of a bar without a `width` when the space is unbounded.
+const DEFAULT_WIDTH: f64 = 20.0;
+
+/// `fill` (between 0 and 1) of `cells` in full blocks, ending with a partial
+/// block for the remaining eighths.
+pub(crate) fn horizontal_bar(fill: f64, cells: usize) -> String {
+    let eighths = (fill.clamp(0.0, 1.0) * (cells * 8) as f64).round() as usize;
+    let mut bar = "█".repeat(eighths / 8);
+    if let Some(part) = EIGHTHS.get((eighths % 8).wrapping_sub(1)) {
+        bar.push(*part);
+    }
+    bar
+}
+
+/// A horizontal progress bar. With a progress between 0 and 1 the fill is
+/// drawn in eighths of a cell; without one, a block bounces back and forth
+/// to show that work is ongoing. Animation follows `Event::Frame`.
+#[derive(Debug, Clone)]
+pub struct ProgressBar {
+    pub progress: Option<f64>,
+    pub width: Option<f64>,
+    pub show_percent: bool,
+    pub fill: Color,
+    pub track: Color,
+    time: State<f64>,
+}
+
+impl ProgressBar {
+    pub fn new(progress: f64) -> Self {
+        ProgressBar {
+            progress: Some(progress),
+            width: None,
+            show_percent: false,
+            fill: Color::Green,
+            track: Color::LightBlack,
+            time: State::new(0.0),
+        }
+    }
+
+    pub fn indeterminate() -> Self {
+        ProgressBar {
+            progress: None,
+            ..ProgressBar::new(0.0)
+        }
+    }
+
+    pub fn width(mut self, width: f64) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    /// Show the percentage after the bar.
+    pub fn show_percent(mut self) -> Self {
+        self.show_percent = true;
+        self
+    }
+
+    pub fn colors(mut self, fill: Color, track: Color) -> Self {
+        self.fill = fill;
+        self.track = track;
+        self
+    }
+
+    fn percent(&self) -> Option<String> {
+        match self.progress {
+            Some(progress) if self.show_percent => {
+                Some(format!(" {:>3.0}%", progress.clamp(0.0, 1.0) * 100.0))
+            }
+            _ => None,
+        }
+    }
+
+    /// The bar drawn over `width` cells.
+    fn bar(&self, width: usize) -> String {
+        match self.progress {
+            Some(progress) => {
+                let mut bar = horizontal_bar(progress, width);
+                bar.push_str(&" ".repeat(width - bar.chars().count()));
+                bar
+            }
+            None => {
+                let block = (width / 4).max(1).min(width);
+                let travel = (width - block) as f64;
+                let phase = (self.time.get() / BOUNCE_PERIOD).fract();
+                let at = (travel * (1.0 - (2.0 * phase - 1.0).abs())).round() as usize;
+                format!(
+                    "{}{}{}",
+                    " ".repeat(at),
+                    "█".repeat(block),
+                    " ".repeat(width - block - at)
+                )
+            }
+        }
+    }
+
+    pub fn render(&self, size: Size, offset: Offset, screen: &mut impl std::io::Write) {
+        let percent = self.percent().unwrap_or_default();
+        let width = columns(size.width).saturating_sub(str_width(&percent));
+        let bar = self.bar(width);
+        write!(
+            screen,
+            "{}{}{}{}{}",
+            cursor::Goto(offset.x as u16, offset.y as u16),
+            Style::new().fg(self.fill).bg(self.track),
+            bar,
+            termion::style::Reset,
+            percent
+        )
+        .unwrap();
+    }
+}
+
+impl Hydrate for ProgressBar {
+    fn hydrate(&mut self, other: &Self) {
+        self.progress = other.progress;
+        self.width = other.width;
+        self.show_percent = other.show_percent;
+        self.fill = other.fill;
+        self.track = other.track;
+    }
+}
+
+impl View for ProgressBar {
+    fn children(&self) -> Vec<AnyView> {
+        Vec::new()
+    }
+
+    fn layout(&self, _children: &[ViewTree], constraint: Constraint) -> Size {
+        let percent = self.percent().map_or(0, |percent| str_width(&percent)) as f64;
+        Size {
+            width: self
+                .width
+                .map_or(bounded(constraint.max_width(), DEFAULT_WIDTH), |width| {
+                    width + percent
+                })
+                .min(constraint.max_width()),
+            height: 1.0,
+        }
+    }
+
+    fn event(&self, _size: Size, _offset: Offset, _children: &[ViewTree], event: &Event) -> bool {
+        if let (Event::Frame(time), None) = (*event, self.progress) {
+            self.time.set(time);
+        }
+        false
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpinnerFrames {
+    Dots,
+    Line,
+    Arc,
+    Quadrant,
+    Bounce,
+}
+
+impl SpinnerFrames {
+    pub fn frames(self) -> &'static [&'static str] {
+        match self {
+            SpinnerFrames::Dots => &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"],
+            SpinnerFrames::Line => &["-", "\\", "|", "/"],
+            SpinnerFrames::Arc => &["◜", "◠", "◝", "◞", "◡", "◟"],
+            SpinnerFrames::Quadrant => &["▖", "▘", "▝", "▗"],
+            SpinnerFrames::Bounce => &["⠁", "⠂", "⠄", "⠂"],
+        }
+    }
+}
+
+/// An animated glyph followed by an optional label. The glyph advances
+/// every `interval` seconds of the frame clock.
+#[derive(Debug, Clone)]
+pub struct Spinner {
+    pub label: String,
+    pub frames: SpinnerFrames,
+    pub interval: f64,
+    pub style: Style,
+    time: State<f64>,
+}
+
+impl Spinner {
+    pub fn new(label: impl ToString) -> Self {
+        Spinner {
+            label: label.to_string(),
+            frames: SpinnerFrames::Dots,
+            interval: 0.08,
+            style: Style::new().fg(Color::Cyan),
+            time: State::new(0.0),
+        }
+    }
+
+    pub fn frames(mut self, frames: SpinnerFrames) -> Self {
+        self.frames = frames;
+        self
+    }
+
+    pub fn interval(mut self, seconds: f64) -> Self {
+        self.interval = seconds.max(0.01);
+        self
+    }
+
+    pub fn styled(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    fn glyph(&self) -> &'static str {
+        let frames = self.frames.frames();
+        frames[(self.time.get() / self.interval) as usize % frames.len()]
+    }
+
+    pub fn render(&self, size: Size, offset: Offset, screen: &mut impl std::io::Write) {
+        let label = if self.label.is_empty() {
+            String::new()
+        } else {
+            format!(" {}", self.label)
+        };
+        let label = slice_columns(&label, 0, columns(size.width).saturating_sub(1));
+        write!(
+            screen,
+            "{}{}{}{}{}",
+            cursor::Goto(offset.x as u16, offset.y as u16),
+            self.style,
+            self.glyph(),
+            termion::style::Reset,
+            label
+        )
+        .unwrap();
+    }
+}
+
+impl Hydrate for Spinner {
+    fn hydrate(&mut self, other: &Self) {
+        self.label = other.label.clone();
+        self.frames = other.frames;
+        self.interval = other.interval;
+        self.style = other.style;
+    }
+}
+
+impl View for Spinner {
+    fn children(&self) -> Vec<AnyView> {
+        Vec::new()
+    }
+
+    fn layout(&self, _children: &[ViewTree], _constraint: Constraint) -> Size {
+        let label = if self.label.is_empty() {
+            0
+        } else {
+            str_width(&self.label) + 1
+        };
+        Size {
+            width: (1 + label) as f64,
+            height: 1.0,
+        }
+    }
+
+    fn event(&self, _size: Size, _offset: Offset, _children: &[ViewTree], event: &Event) -> bool {
+        if let Event::Frame(time) = *event {
+            self.time.set(time);
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unbounded_width_falls_back_to_the_default() {
+        for bar in [
+            ProgressBar::new(0.5).show_percent(),
+            ProgressBar::indeterminate(),
+        ] {
+            let tree = ViewTree::new(bar.clone().any_view());
+            tree.layout(Constraint::unbounded());
+            let size = tree.view.size.get();
+            assert_eq!(size, Size::new(DEFAULT_WIDTH, 1.0));
+
+            let mut screen = Vec::new();
+            bar.render(size, Offset::new(1.0, 1.0), &mut screen);
+            // Drawing an unbounded width is cut to a screen.
+            bar.render(
+                Size::new(f64::INFINITY, 1.0),
+                Offset::new(1.0, 1.0),
+                &mut screen,
+            );
+        }
+    }
+}
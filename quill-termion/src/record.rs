@@ -0,0 +1,228 @@
+// Recording of the input of `run`, one JSON line per frame that had input,
+// with the times of the idle frames before it, and its replay through the
+// same hydrate, layout and event steps.
+
+use crate::{place_caret, Session};
+use quill::*;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::{thread, time};
+use termion::input::TermRead;
+use termion::raw::IntoRawMode;
+use termion::screen::IntoAlternateScreen;
+use termion::{clear, cursor};
+
+/// The input of one frame of a recording. Frames with events or a new size
+/// are recorded, and carry the times of the idle frames before them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FrameRecord {
+    /// Frames are numbered from 0.
+    pub frame: u64,
+    /// Seconds since the start, as given to `Event::Frame`.
+    pub time: f64,
+    /// The size the UI is laid out in, when it changed since the previous
+    /// frame.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<Size>,
+    /// Input delivered before the frame, in order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<Event>,
+    /// Times of the frames without input since the previous record, which
+    /// are numbered just before this one.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub idle: Vec<f64>,
+}
+
+// Idle frames are written at least this often, so that a crash loses at
+// most a second of them.
+const IDLE_FRAMES: usize = 60;
+
+/// Writes the frames of `run` to a file. Every record is flushed, so the
+/// recording survives a crash of the app. The idle frames still pending are
+/// written when the recorder is dropped.
+pub(crate) struct Recorder {
+    out: BufWriter<File>,
+    frame: u64,
+    size: Option<Size>,
+    events: Vec<Event>,
+    idle: Vec<f64>,
+}
+
+impl Recorder {
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Recorder> {
+        Ok(Recorder {
+            out: BufWriter::new(File::create(path)?),
+            frame: 0,
+            size: None,
+            events: Vec::new(),
+            idle: Vec::new(),
+        })
+    }
+
+    /// Record an event delivered to the app, for the next frame.
+    pub fn event(&mut self, event: Event) {
+        self.events.push(event);
+    }
+
+    /// Record a frame. Frames without input are kept for the next record.
+    pub fn frame(&mut self, time: f64, size: Size) -> io::Result<()> {
+        let changed = Some(size).filter(|size| Some(*size) != self.size);
+        self.frame += 1;
+        self.size = Some(size);
+        if changed.is_some() || !self.events.is_empty() || self.idle.len() >= IDLE_FRAMES {
+            self.write(time, changed)
+        } else {
+            self.idle.push(time);
+            Ok(())
+        }
+    }
+
+    /// Write the last frame recorded, with the events and idle frames
+    /// before it.
+    fn write(&mut self, time: f64, size: Option<Size>) -> io::Result<()> {
+        let record = FrameRecord {
+            frame: self.frame - 1,
+            time,
+            size,
+            events: std::mem::take(&mut self.events),
+            idle: std::mem::take(&mut self.idle),
+        };
+        serde_json::to_writer(&mut self.out, &record)?;
+        writeln!(self.out)?;
+        self.out.flush()
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        if let Some(time) = self.idle.pop() {
+            let _ = self.write(time, None);
+        }
+    }
+}
+
+/// Read the frames recorded by `run`.
+pub fn read_recording(path: impl AsRef<Path>) -> io::Result<Vec<FrameRecord>> {
+    BufReader::new(File::open(path)?)
+        .lines()
+        .filter(|line| !matches!(line, Ok(line) if line.trim().is_empty()))
+        .map(|line| {
+            serde_json::from_str(&line?)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+        })
+        .collect()
+}
+
+/// How `replay` plays a recording.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReplayOptions {
+    /// Play without a terminal: nothing is drawn and frames are not timed.
+    pub headless: bool,
+    /// The last frame to play. The whole recording is played by default.
+    pub stop_at: Option<u64>,
+}
+
+/// Feed a recording made by `run` back to `app`, frame by frame with the
+/// recorded times and sizes, idle frames included. Returns the view tree as
+/// of the last frame played.
+///
+/// Unless headless, the frames are drawn at their recorded pace, and the
+/// last one stays on screen until a key is pressed.
+pub fn replay(
+    app: impl View + Clone,
+    path: impl AsRef<Path>,
+    options: ReplayOptions,
+) -> io::Result<ViewTree> {
+    let frames = read_recording(path)?;
+    let mut session = Session::new(app);
+    let mut screen = if options.headless {
+        None
+    } else {
+        Some(stdout_screen()?)
+    };
+    let start = time::Instant::now();
+    let mut size = Size::zero();
+    'frames: for record in frames {
+        let FrameRecord {
+            frame: last,
+            time: last_time,
+            size: new_size,
+            events,
+            idle,
+        } = record;
+        let first = last.saturating_sub(idle.len() as u64);
+        let idle = idle
+            .into_iter()
+            .enumerate()
+            .map(|(nth, time)| (first + nth as u64, time, None, Vec::new()));
+        for (frame, time, new_size, events) in
+            idle.chain(std::iter::once((last, last_time, new_size, events)))
+        {
+            if options.stop_at.is_some_and(|stop_at| frame > stop_at) {
+                break 'frames;
+            }
+            size = new_size.unwrap_or(size);
+            for event in events {
+                if !session.event(event) {
+                    break 'frames;
+                }
+            }
+            session.frame(time, size);
+            if let Some(screen) = &mut screen {
+                let at = time::Duration::from_secs_f64(time.max(0.0));
+                thread::sleep(at.saturating_sub(start.elapsed()));
+                write!(screen, "{}", clear::All)?;
+                let caret = session.render(screen);
+                place_caret(caret, screen)?;
+                screen.flush()?;
+            }
+        }
+    }
+    if let Some(mut screen) = screen {
+        io::stdin().keys().next();
+        write!(screen, "{}", cursor::Show)?;
+        screen.flush()?;
+    }
+    Ok(session.tree)
+}
+
+fn stdout_screen() -> io::Result<impl Write> {
+    let mut screen = io::stdout().into_raw_mode()?.into_alternate_screen()?;
+    write!(screen, "{}{}", clear::All, cursor::Hide)?;
+    Ok(screen)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn idle_frames_are_written_with_the_next_record() {
+        let path = std::env::temp_dir().join(format!("quill-record-{}.jsonl", std::process::id()));
+        let mut recorder = Recorder::create(&path).unwrap();
+        let size = Size::new(80.0, 24.0);
+        for frame in 0..5 {
+            recorder.frame(frame as f64, size).unwrap();
+        }
+        recorder.event(Event::KeyPress(KeyCode::Enter, Modifiers::none()));
+        recorder.frame(5.0, size).unwrap();
+        recorder.frame(6.0, Size::new(40.0, 24.0)).unwrap();
+        recorder.frame(7.0, Size::new(40.0, 24.0)).unwrap();
+        recorder.frame(8.0, Size::new(40.0, 24.0)).unwrap();
+        drop(recorder);
+        let frames = read_recording(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let stored: Vec<u64> = frames.iter().map(|record| record.frame).collect();
+        assert_eq!(stored, [0, 5, 6, 8]);
+        assert_eq!(frames[0].size, Some(size));
+        assert_eq!(frames[1].size, None);
+        assert_eq!(frames[1].events.len(), 1);
+        assert_eq!(frames[1].idle, [1.0, 2.0, 3.0, 4.0]);
+        // The frames left over are written on drop.
+        assert_eq!(frames[3].idle, [7.0]);
+        assert_eq!(frames[3].time, 8.0);
+    }
+}
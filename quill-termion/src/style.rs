@@ -0,0 +1,144 @@
+use std::fmt;
+use termion::color::{self, AnsiValue, Rgb};
+use termion::style as tstyle;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    LightBlack,
+    LightRed,
+    LightGreen,
+    LightYellow,
+    LightBlue,
+    LightMagenta,
+    LightCyan,
+    LightWhite,
+    Ansi(u8),
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    fn ansi(self) -> Option<u8> {
+        Some(match self {
+            Color::Black => 0,
+            Color::Red => 1,
+            Color::Green => 2,
+            Color::Yellow => 3,
+            Color::Blue => 4,
+            Color::Magenta => 5,
+            Color::Cyan => 6,
+            Color::White => 7,
+            Color::LightBlack => 8,
+            Color::LightRed => 9,
+            Color::LightGreen => 10,
+            Color::LightYellow => 11,
+            Color::LightBlue => 12,
+            Color::LightMagenta => 13,
+            Color::LightCyan => 14,
+            Color::LightWhite => 15,
+            Color::Ansi(n) => n,
+            Color::Rgb(..) => return None,
+        })
+    }
+
+    fn write_fg(self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self, self.ansi()) {
+            (Color::Rgb(r, g, b), _) => write!(f, "{}", color::Fg(Rgb(r, g, b))),
+            (_, Some(n)) => write!(f, "{}", color::Fg(AnsiValue(n))),
+            _ => Ok(()),
+        }
+    }
+
+    fn write_bg(self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self, self.ansi()) {
+            (Color::Rgb(r, g, b), _) => write!(f, "{}", color::Bg(Rgb(r, g, b))),
+            (_, Some(n)) => write!(f, "{}", color::Bg(AnsiValue(n))),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Text attributes for terminal output. `Display` writes the escape codes,
+/// preceded by a reset so styles never leak from one piece of text to the
+/// next.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Style {
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+    pub bold: bool,
+    pub dim: bool,
+    pub underline: bool,
+    pub reverse: bool,
+}
+
+impl Style {
+    pub fn new() -> Style {
+        Style::default()
+    }
+
+    pub fn fg(mut self, color: Color) -> Self {
+        self.fg = Some(color);
+        self
+    }
+
+    pub fn bg(mut self, color: Color) -> Self {
+        self.bg = Some(color);
+        self
+    }
+
+    pub fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    pub fn dim(mut self) -> Self {
+        self.dim = true;
+        self
+    }
+
+    pub fn underline(mut self) -> Self {
+        self.underline = true;
+        self
+    }
+
+    pub fn reverse(mut self) -> Self {
+        self.reverse = true;
+        self
+    }
+
+    pub fn is_plain(&self) -> bool {
+        *self == Style::default()
+    }
+}
+
+impl fmt::Display for Style {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", tstyle::Reset)?;
+        if self.bold {
+            write!(f, "{}", tstyle::Bold)?;
+        }
+        if self.dim {
+            write!(f, "{}", tstyle::Faint)?;
+        }
+        if self.underline {
+            write!(f, "{}", tstyle::Underline)?;
+        }
+        if self.reverse {
+            write!(f, "{}", tstyle::Invert)?;
+        }
+        if let Some(fg) = self.fg {
+            fg.write_fg(f)?;
+        }
+        if let Some(bg) = self.bg {
+            bg.write_bg(f)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,615 @@
+use crate::style::Style;
+use crate::text::{columns, slice_columns, str_width};
+use crate::TermText;
+use quill::*;
+use std::cmp::Ordering;
+use std::rc::Rc;
+
+// Seconds between the presses of a double-click, on the frame clock.
+const DOUBLE_CLICK: f64 = 0.4;
+
+// Width of a table in unbounded space.
+const DEFAULT_WIDTH: f64 = 80.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColumnWidth {
+    /// A number of terminal columns.
+    Fixed(f64),
+    /// A percentage of the table width.
+    Percent(f64),
+    /// A share of the space left over by the other columns.
+    Flex(f64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextAlign {
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Column {
+    pub header: String,
+    pub width: ColumnWidth,
+    pub align: TextAlign,
+    pub sortable: bool,
+}
+
+impl Column {
+    pub fn new(header: impl ToString, width: ColumnWidth) -> Self {
+        Column {
+            header: header.to_string(),
+            width,
+            align: TextAlign::Left,
+            sortable: true,
+        }
+    }
+
+    pub fn align(mut self, align: TextAlign) -> Self {
+        self.align = align;
+        self
+    }
+
+    pub fn sortable(mut self, sortable: bool) -> Self {
+        self.sortable = sortable;
+        self
+    }
+}
+
+type Rows = Rc<Vec<Vec<String>>>;
+
+// Sorting is redone only when the data or the sort key changes.
+#[derive(Clone)]
+struct SortCache {
+    rows: Rows,
+    sort: Option<(usize, SortOrder)>,
+    order: Rc<Vec<usize>>,
+}
+
+/// A column drag in progress: the column, where the drag started and the
+/// column width at that point.
+type Resize = Option<(usize, f64, usize)>;
+
+/// Tabular data with a header row. Rows are virtualized, so only the visible
+/// ones are built. `selected` holds the index of the selected row in `rows`,
+/// independent of the sort order.
+#[derive(Clone)]
+pub struct Table {
+    pub columns: Vec<Column>,
+    pub rows: Rows,
+    pub selected: State<usize>,
+    pub height: Option<f64>,
+    pub on_activate: Option<Rc<dyn Fn(usize)>>,
+    /// The sort set by `sorted_by`. Header clicks change the sort until this
+    /// changes.
+    pub sort_by: Option<(usize, SortOrder)>,
+    sort: State<Option<(usize, SortOrder)>>,
+    widths: State<Vec<Option<usize>>>,
+    resize: State<Resize>,
+    scroll: State<usize>,
+    last_click: State<Option<(usize, f64)>>,
+    cache: State<Option<SortCache>>,
+    // Width from the last layout, used to size the columns of the body. A
+    // change asks for a rebuild, so the rows are drawn at the new width.
+    viewport: State<f64>,
+    focused: State<bool>,
+}
+
+impl std::fmt::Debug for Table {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Table")
+            .field("columns", &self.columns.len())
+            .field("rows", &self.rows.len())
+            .field("selected", &self.selected)
+            .field("sort", &self.sort)
+            .field("scroll", &self.scroll)
+            .finish()
+    }
+}
+
+fn compare_cells(a: &str, b: &str) -> Ordering {
+    match (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+        _ => a.cmp(b),
+    }
+}
+
+fn fit(text: &str, width: usize, align: TextAlign) -> String {
+    let text = if str_width(text) > width {
+        if width == 0 {
+            return String::new();
+        }
+        format!("{}…", slice_columns(text, 0, width - 1))
+    } else {
+        text.to_string()
+    };
+    let pad = width.saturating_sub(str_width(&text));
+    let (left, right) = match align {
+        TextAlign::Left => (0, pad),
+        TextAlign::Right => (pad, 0),
+        TextAlign::Center => (pad / 2, pad - pad / 2),
+    };
+    format!("{}{}{}", " ".repeat(left), text, " ".repeat(right))
+}
+
+impl Table {
+    pub fn new(selected: State<usize>) -> Self {
+        Table {
+            columns: Vec::new(),
+            rows: Rc::new(Vec::new()),
+            selected,
+            height: None,
+            on_activate: None,
+            sort_by: None,
+            sort: State::new(None),
+            widths: State::new(Vec::new()),
+            resize: State::new(None),
+            scroll: State::new(0),
+            last_click: State::new(None),
+            cache: State::new(None),
+            viewport: State::new(0.0),
+            focused: State::new(false),
+        }
+    }
+
+    pub fn with_column(mut self, column: Column) -> Self {
+        self.columns.push(column);
+        self
+    }
+
+    pub fn rows(mut self, rows: Rows) -> Self {
+        self.rows = rows;
+        self
+    }
+
+    pub fn height(mut self, height: f64) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    pub fn sorted_by(mut self, column: usize, order: SortOrder) -> Self {
+        self.sort_by = Some((column, order));
+        self.sort.set(self.sort_by);
+        self
+    }
+
+    /// Called with the row index on Enter or double-click.
+    pub fn on_activate(mut self, cb: impl Fn(usize) + 'static) -> Self {
+        self.on_activate = Some(Rc::new(cb));
+        self
+    }
+
+    /// Row indices in display order.
+    pub fn order(&self) -> Rc<Vec<usize>> {
+        let sort = self.sort.get();
+        let cached = self.cache.with(|cache| match cache {
+            Some(cache) if Rc::ptr_eq(&cache.rows, &self.rows) && cache.sort == sort => {
+                Some(cache.order.clone())
+            }
+            _ => None,
+        });
+        if let Some(order) = cached {
+            return order;
+        }
+        let mut order: Vec<usize> = (0..self.rows.len()).collect();
+        if let Some((column, dir)) = sort {
+            let empty = String::new();
+            let cell = |row: usize| self.rows[row].get(column).unwrap_or(&empty);
+            order.sort_by(|a, b| {
+                let ord = compare_cells(cell(*a), cell(*b));
+                match dir {
+                    SortOrder::Ascending => ord,
+                    SortOrder::Descending => ord.reverse(),
+                }
+            });
+        }
+        let order = Rc::new(order);
+        self.cache.set(Some(SortCache {
+            rows: self.rows.clone(),
+            sort,
+            order: order.clone(),
+        }));
+        order
+    }
+
+    /// Resolve the column widths for a table `total` columns wide.
+    pub fn column_widths(&self, total: f64) -> Vec<usize> {
+        let total = columns(total) as f64;
+        let overrides = self.widths.get();
+        let separators = self.columns.len().saturating_sub(1) as f64;
+        let available = (total - separators).max(0.0);
+        let mut widths = vec![0.0; self.columns.len()];
+        let mut flex = 0.0;
+        for (nth, column) in self.columns.iter().enumerate() {
+            match (overrides.get(nth).copied().flatten(), column.width) {
+                (Some(w), _) => widths[nth] = w as f64,
+                (None, ColumnWidth::Fixed(w)) => widths[nth] = w,
+                (None, ColumnWidth::Percent(p)) => widths[nth] = (available * p / 100.0).floor(),
+                (None, ColumnWidth::Flex(f)) => flex += f,
+            }
+        }
+        let left = (available - widths.iter().sum::<f64>()).max(0.0);
+        for (nth, column) in self.columns.iter().enumerate() {
+            if let (None, ColumnWidth::Flex(f)) =
+                (overrides.get(nth).copied().flatten(), column.width)
+            {
+                widths[nth] = (left * f / flex).floor();
+            }
+        }
+        widths.into_iter().map(columns).collect()
+    }
+
+    fn format_row(&self, cells: &[String], widths: &[usize]) -> String {
+        let empty = String::new();
+        let cells: Vec<String> = self
+            .columns
+            .iter()
+            .zip(widths)
+            .enumerate()
+            .map(|(nth, (column, width))| {
+                fit(cells.get(nth).unwrap_or(&empty), *width, column.align)
+            })
+            .collect();
+        cells.join("│")
+    }
+
+    fn header(&self, widths: &[usize]) -> String {
+        let sort = self.sort.get();
+        let headers: Vec<String> = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(nth, column)| match sort {
+                Some((col, SortOrder::Ascending)) if col == nth => format!("{} ▲", column.header),
+                Some((col, SortOrder::Descending)) if col == nth => format!("{} ▼", column.header),
+                _ => column.header.clone(),
+            })
+            .collect();
+        self.format_row(&headers, widths)
+    }
+
+    fn page(&self, size: Size) -> usize {
+        (size.height - 1.0).max(1.0) as usize
+    }
+
+    fn select(&self, size: Size, position: usize) {
+        let order = self.order();
+        if order.is_empty() {
+            return;
+        }
+        let position = position.min(order.len() - 1);
+        self.selected.set(order[position]);
+        let page = self.page(size);
+        let scroll = self.scroll.get();
+        if position < scroll {
+            self.scroll.set(position);
+        } else if position >= scroll + page {
+            self.scroll.set(position + 1 - page);
+        }
+    }
+
+    fn position(&self) -> usize {
+        let selected = self.selected.get();
+        self.order()
+            .iter()
+            .position(|row| *row == selected)
+            .unwrap_or(0)
+    }
+
+    fn activate(&self) -> bool {
+        match &self.on_activate {
+            Some(cb) if self.selected.get() < self.rows.len() => {
+                cb(self.selected.get());
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn key_press(&self, size: Size, key: KeyCode) -> bool {
+        let position = self.position();
+        let page = self.page(size);
+        match key {
+            KeyCode::Up => self.select(size, position.saturating_sub(1)),
+            KeyCode::Down => self.select(size, position + 1),
+            KeyCode::PageUp => self.select(size, position.saturating_sub(page)),
+            KeyCode::PageDown => self.select(size, position + page),
+            KeyCode::Home => self.select(size, 0),
+            KeyCode::End => self.select(size, usize::MAX),
+            KeyCode::Enter => return self.activate(),
+            _ => return false,
+        }
+        true
+    }
+
+    /// The column whose right border sits at `col`, if any.
+    fn border_at(widths: &[usize], col: usize) -> Option<usize> {
+        let mut x = 0;
+        for (nth, width) in widths.iter().enumerate() {
+            x += width;
+            if col == x && nth + 1 < widths.len() {
+                return Some(nth);
+            }
+            x += 1;
+        }
+        None
+    }
+
+    fn column_at(widths: &[usize], col: usize) -> Option<usize> {
+        let mut x = 0;
+        for (nth, width) in widths.iter().enumerate() {
+            if col < x + width {
+                return Some(nth);
+            }
+            x += width + 1;
+        }
+        None
+    }
+
+    fn header_press(&self, size: Size, col: usize, x: f64) {
+        let widths = self.column_widths(size.width);
+        if let Some(nth) = Self::border_at(&widths, col) {
+            self.resize.set(Some((nth, x, widths[nth])));
+        } else if let Some(nth) = Self::column_at(&widths, col) {
+            if !self.columns[nth].sortable {
+                return;
+            }
+            let sort = match self.sort.get() {
+                Some((c, SortOrder::Ascending)) if c == nth => Some((nth, SortOrder::Descending)),
+                Some((c, SortOrder::Descending)) if c == nth => None,
+                _ => Some((nth, SortOrder::Ascending)),
+            };
+            self.sort.set(sort);
+        }
+    }
+
+    fn drag(&self, x: f64) {
+        if let Some((nth, start, width)) = self.resize.get() {
+            let width = (width as f64 + x - start).max(1.0) as usize;
+            self.widths.update(|widths| {
+                if widths.len() < self.columns.len() {
+                    widths.resize(self.columns.len(), None);
+                }
+                widths[nth] = Some(width);
+            });
+        }
+    }
+}
+
+impl Hydrate for Table {
+    fn hydrate(&mut self, other: &Self) {
+        // Dragged widths belong to the old columns.
+        if other.columns != self.columns {
+            self.widths.set(Vec::new());
+            self.resize.set(None);
+        }
+        self.columns = other.columns.clone();
+        self.rows = other.rows.clone();
+        self.selected = other.selected.clone();
+        self.height = other.height;
+        self.on_activate = other.on_activate.clone();
+        if other.sort_by != self.sort_by {
+            self.sort_by = other.sort_by;
+            self.sort.set(other.sort_by);
+        }
+    }
+}
+
+impl View for Table {
+    fn body(&self) -> AnyView {
+        let widths = self.column_widths(self.viewport.get());
+        let mut stack = Stack::new();
+        stack.push(TermText::new(self.header(&widths)).styled(Style::new().bold().underline()));
+
+        let order = self.order();
+        let rows = self.rows.clone();
+        let selected = self.selected.get();
+        let focused = self.focused.get();
+        let this = self.clone();
+        stack.push(
+            VirtualList::new(order.len(), 1.0, move |nth| {
+                let row = order[nth];
+                let style = match (row == selected, focused) {
+                    (true, true) => Style::new().reverse(),
+                    (true, false) => Style::new().bold(),
+                    _ => Style::new(),
+                };
+                TermText::new(this.format_row(&rows[row], &widths))
+                    .styled(style)
+                    .any_view()
+            })
+            .scroll(self.scroll.clone()),
+        );
+        stack.any_view()
+    }
+
+    fn layout(&self, children: &[ViewTree], constraint: Constraint) -> Size {
+        let width = bounded(constraint.max_width(), DEFAULT_WIDTH);
+        if width != self.viewport.get() {
+            self.viewport.set(width);
+            request_rebuild();
+        }
+        let height = self
+            .height
+            .unwrap_or_else(|| (self.rows.len() as f64 + 1.0).min(constraint.max_height()));
+        let inner = Constraint::new(width, height);
+        for child in children {
+            child.layout(inner);
+        }
+        Size { width, height }
+    }
+
+    fn focusable(&self) -> bool {
+        true
+    }
+
+    fn set_focus(&self, focused: bool) {
+        self.focused.set(focused);
+    }
+
+    fn event(&self, size: Size, offset: Offset, _children: &[ViewTree], event: &Event) -> bool {
+        let bounds = Rect::new(offset, size);
+        match *event {
+            Event::MousePress(MouseButton::Left, x, y) if bounds.contains(x, y) => {
+                let col = (x - offset.x) as usize;
+                let line = (y - offset.y) as usize;
+                if line == 0 {
+                    self.header_press(size, col, x);
+                } else {
+                    let position = self.scroll.get() + line - 1;
+                    let order = self.order();
+                    if position < order.len() {
+                        self.select(size, position);
+                        let now = quill::frame_time();
+                        let double = matches!(self.last_click.get(),
+                            Some((prev, at)) if prev == position && now - at < DOUBLE_CLICK);
+                        if double {
+                            self.last_click.set(None);
+                            self.activate();
+                        } else {
+                            self.last_click.set(Some((position, now)));
+                        }
+                    }
+                }
+                true
+            }
+            Event::MouseHold(x, _) if self.resize.get().is_some() => {
+                self.drag(x);
+                true
+            }
+            Event::MouseRelease(x, _) if self.resize.get().is_some() => {
+                self.drag(x);
+                self.resize.set(None);
+                true
+            }
+            // Ctrl combinations are left to the containers, e.g. `Tabs`.
+            Event::KeyPress(key, mods) if !mods.ctrl => self.key_press(size, key),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> Table {
+        let row = |cells: [&str; 3]| cells.iter().map(|cell| cell.to_string()).collect();
+        Table::new(State::new(0))
+            .with_column(Column::new("id", ColumnWidth::Fixed(4.0)))
+            .with_column(Column::new("name", ColumnWidth::Percent(50.0)))
+            .with_column(Column::new("size", ColumnWidth::Flex(1.0)))
+            .rows(Rc::new(vec![row(["2", "b", "10"]), row(["10", "a", "9"])]))
+    }
+
+    /// The header of a laid out table, after the rebuild its layout asks for.
+    fn header(table: Table, constraint: Constraint) -> (ViewTree, String) {
+        let mut tree = ViewTree::new(table.clone().any_view());
+        tree.layout(constraint);
+        if take_rebuild() {
+            tree.perform_hydrate(table.any_view());
+            tree.layout(constraint);
+        }
+        let header = &tree.children[0].children[0].view;
+        let header = header.downcast_ref::<TermText>().unwrap().text.clone();
+        (tree, header)
+    }
+
+    #[test]
+    fn unbounded_width_falls_back_to_the_default() {
+        let (tree, header) = header(table(), Constraint::unbounded());
+        assert_eq!(tree.view.size.get(), Size::new(DEFAULT_WIDTH, 3.0));
+        assert_eq!(str_width(&header), DEFAULT_WIDTH as usize);
+        // Infinite totals are cut to a screen.
+        assert!(table()
+            .column_widths(f64::INFINITY)
+            .iter()
+            .all(|width| *width <= u16::MAX as usize));
+    }
+
+    #[test]
+    fn column_widths_share_the_rest() {
+        let table = table();
+        // 38 columns between the two separators.
+        assert_eq!(table.column_widths(40.0), [4, 19, 15]);
+        table.widths.set(vec![Some(10)]);
+        assert_eq!(table.column_widths(40.0), [10, 19, 9]);
+        // Flex columns shrink to nothing first.
+        assert_eq!(table.column_widths(10.0), [10, 4, 0]);
+    }
+
+    #[test]
+    fn cells_compare_as_numbers_when_they_can() {
+        assert_eq!(compare_cells("9", "10"), Ordering::Less);
+        assert_eq!(compare_cells(" 2 ", "10"), Ordering::Less);
+        assert_eq!(compare_cells("b", "a"), Ordering::Greater);
+        assert_eq!(compare_cells("10", "a"), Ordering::Less);
+        assert_eq!(compare_cells("NaN", "1"), Ordering::Equal);
+    }
+
+    #[test]
+    fn header_columns_and_borders() {
+        let widths = [4, 19, 15];
+        assert_eq!(Table::border_at(&widths, 4), Some(0));
+        assert_eq!(Table::border_at(&widths, 24), Some(1));
+        // The right edge of the table doesn't resize.
+        assert_eq!(Table::border_at(&widths, 40), None);
+        assert_eq!(Table::border_at(&widths, 3), None);
+        assert_eq!(Table::column_at(&widths, 0), Some(0));
+        assert_eq!(Table::column_at(&widths, 3), Some(0));
+        assert_eq!(Table::column_at(&widths, 5), Some(1));
+        assert_eq!(Table::column_at(&widths, 39), Some(2));
+        assert_eq!(Table::column_at(&widths, 40), None);
+    }
+
+    #[test]
+    fn header_clicks_cycle_the_sort() {
+        let table = table();
+        let size = Size::new(40.0, 5.0);
+        table.header_press(size, 6, 6.0);
+        assert_eq!(table.sort.get(), Some((1, SortOrder::Ascending)));
+        assert_eq!(*table.order(), [1, 0]);
+        table.header_press(size, 6, 6.0);
+        assert_eq!(table.sort.get(), Some((1, SortOrder::Descending)));
+        assert_eq!(*table.order(), [0, 1]);
+        table.header_press(size, 6, 6.0);
+        assert_eq!(table.sort.get(), None);
+        // Another column starts over.
+        table.header_press(size, 6, 6.0);
+        table.header_press(size, 0, 0.0);
+        assert_eq!(table.sort.get(), Some((0, SortOrder::Ascending)));
+        assert_eq!(*table.order(), [0, 1]);
+
+        let table = Table {
+            columns: vec![Column::new("id", ColumnWidth::Fixed(4.0)).sortable(false)],
+            ..table
+        };
+        table.sort.set(None);
+        table.header_press(size, 0, 0.0);
+        assert_eq!(table.sort.get(), None);
+    }
+
+    #[test]
+    fn new_columns_reset_dragged_widths() {
+        let mut table = table();
+        let size = Size::new(40.0, 5.0);
+        table.header_press(size, 4, 4.0);
+        table.drag(7.0);
+        assert_eq!(table.column_widths(40.0)[0], 7);
+
+        // The same columns keep the width.
+        table.hydrate(&self::table());
+        assert_eq!(table.column_widths(40.0)[0], 7);
+
+        table.hydrate(&self::table().with_column(Column::new("extra", ColumnWidth::Fixed(2.0))));
+        assert_eq!(table.widths.get(), []);
+        assert_eq!(table.resize.get(), None);
+    }
+}
@@ -0,0 +1,392 @@
+use crate::style::Style;
+use crate::text::{slice_columns, str_width};
+use quill::*;
+use termion::cursor;
+
+// A tab label placed on the bar: (tab index, column, width).
+type Span = (usize, usize, usize);
+
+/// A tab bar showing one page at a time, bound to the index of the selected
+/// page.
+///
+/// Pages that are switched away from keep their subtree, and with it their
+/// state, until they are shown again. When the labels don't fit, the bar
+/// scrolls to keep the selected tab visible and shows `‹` and `›` at the ends.
+#[derive(Debug, Clone)]
+pub struct Tabs {
+    pub labels: Vec<String>,
+    pub pages: Vec<AnyView>,
+    pub selected: State<usize>,
+    // First tab shown on the bar.
+    scroll: State<usize>,
+    focused: State<bool>,
+}
+
+impl Tabs {
+    pub fn new(selected: State<usize>) -> Self {
+        Tabs {
+            labels: Vec::new(),
+            pages: Vec::new(),
+            selected,
+            scroll: State::new(0),
+            focused: State::new(false),
+        }
+    }
+
+    pub fn with_tab(mut self, label: impl ToString, page: impl View) -> Self {
+        self.labels.push(label.to_string());
+        self.pages.push(page.any_view());
+        self
+    }
+
+    fn current(&self) -> usize {
+        self.selected.get().min(self.pages.len().saturating_sub(1))
+    }
+
+    fn select(&self, nth: usize) {
+        if nth < self.pages.len() && nth != self.selected.get() {
+            self.selected.set(nth);
+        }
+    }
+
+    // Move the selection by `delta` tabs, wrapping around.
+    fn step(&self, delta: isize) {
+        let len = self.pages.len() as isize;
+        if len > 0 {
+            self.select((self.current() as isize + delta).rem_euclid(len) as usize);
+        }
+    }
+
+    /// The tabs that fit on a bar of `width` columns when starting at
+    /// `scroll`, and whether more tabs follow.
+    fn spans(&self, width: usize, scroll: usize) -> (Vec<Span>, bool) {
+        let mut spans = Vec::new();
+        let mut col = usize::from(scroll > 0);
+        for nth in scroll..self.labels.len() {
+            let tab = str_width(&self.labels[nth]) + 2;
+            // Keep a column for `›` unless this is the last tab.
+            let limit = if nth + 1 < self.labels.len() {
+                width.saturating_sub(1)
+            } else {
+                width
+            };
+            if col + tab > limit {
+                if spans.is_empty() {
+                    spans.push((nth, col, limit.saturating_sub(col)));
+                }
+                return (spans, true);
+            }
+            spans.push((nth, col, tab));
+            col += tab + 1;
+        }
+        (spans, false)
+    }
+
+    /// Width of the whole tab bar.
+    fn bar_width(&self) -> f64 {
+        let bar: usize = self.labels.iter().map(|label| str_width(label) + 3).sum();
+        bar.saturating_sub(1) as f64
+    }
+
+    // Scroll the bar just enough for the selected tab to be visible.
+    fn fit_scroll(&self, width: usize) {
+        let selected = self.current();
+        let mut scroll = self.scroll.get().min(selected);
+        while scroll < selected
+            && !self
+                .spans(width, scroll)
+                .0
+                .iter()
+                .any(|&(nth, ..)| nth == selected)
+        {
+            scroll += 1;
+        }
+        if scroll != self.scroll.get() {
+            self.scroll.set(scroll);
+        }
+    }
+
+    fn click(&self, width: usize, col: usize) {
+        let scroll = self.scroll.get();
+        let (spans, more) = self.spans(width, scroll);
+        if scroll > 0 && col == 0 {
+            self.step(-1);
+        } else if more && width.checked_sub(1) == Some(col) {
+            self.step(1);
+        } else if let Some(&(nth, ..)) = spans
+            .iter()
+            .find(|&&(_, start, tab)| col >= start && col < start + tab)
+        {
+            self.select(nth);
+        }
+    }
+
+    /// Draw the tab bar. The page draws itself.
+    pub fn render(&self, size: Size, offset: Offset, screen: &mut impl std::io::Write) {
+        let width = size.width.max(0.0) as usize;
+        let scroll = self.scroll.get();
+        let (spans, more) = self.spans(width, scroll);
+        let selected = self.current();
+        let y = offset.y as u16;
+        let goto = |col: usize| cursor::Goto(offset.x as u16 + col as u16, y);
+        let dim = Style::new().dim();
+        write!(screen, "{}{}", goto(0), " ".repeat(width)).unwrap();
+        if scroll > 0 {
+            write!(screen, "{}{}‹", goto(0), dim).unwrap();
+        }
+        for &(nth, col, tab) in spans.iter() {
+            let style = match (nth == selected, self.focused.get()) {
+                (true, true) => Style::new().reverse(),
+                (true, false) => Style::new().bold().underline(),
+                (false, _) => Style::new(),
+            };
+            let label = slice_columns(&format!(" {} ", self.labels[nth]), 0, tab);
+            write!(screen, "{}{}{}", goto(col), style, label).unwrap();
+            if col + tab < width && nth + 1 < self.labels.len() {
+                write!(screen, "{}{}│", goto(col + tab), dim).unwrap();
+            }
+        }
+        if more && width > 0 {
+            write!(screen, "{}{}›", goto(width - 1), dim).unwrap();
+        }
+        write!(screen, "{}", termion::style::Reset).unwrap();
+    }
+}
+
+impl Hydrate for Tabs {
+    fn hydrate(&mut self, other: &Self) {
+        self.labels = other.labels.clone();
+        self.pages = other.pages.clone();
+        self.selected = other.selected.clone();
+    }
+}
+
+impl View for Tabs {
+    fn children(&self) -> Vec<AnyView> {
+        if self.pages.is_empty() {
+            return Vec::new();
+        }
+        let nth = self.current();
+        vec![self.pages[nth].clone().with_key(Key(nth as u32))]
+    }
+
+    fn retain_children(&self) -> bool {
+        true
+    }
+
+    fn layout(&self, children: &[ViewTree], mut constraint: Constraint) -> Size {
+        constraint.sub_height(1.0);
+        let mut size = Size {
+            width: 0.0,
+            height: 1.0,
+        };
+        for child in children {
+            child.layout(constraint);
+            let page = child.view.size.get();
+            size.width = size.width.max(page.width);
+            size.height += page.height;
+        }
+        size.width = size.width.max(self.bar_width().min(constraint.max_width()));
+        self.fit_scroll(size.width as usize);
+        size
+    }
+
+    // The bar scrolls, so only the page limits the minimum width.
+    fn intrinsic(&self, children: &[ViewTree], kind: Intrinsic, extent: f64) -> f64 {
+        let extent = if kind.is_width() {
+            extent - 1.0
+        } else {
+            extent
+        };
+        let page = children
+            .first()
+            .map_or(0.0, |page| page.intrinsic(kind, extent.max(0.0)));
+        match kind {
+            Intrinsic::MinWidth => page,
+            Intrinsic::MaxWidth => page.max(self.bar_width()),
+            Intrinsic::MinHeight | Intrinsic::MaxHeight => page + 1.0,
+        }
+    }
+
+    fn set_offset(&self, children: &[ViewTree], offset: Offset) {
+        for child in children {
+            child.set_offset(Offset {
+                x: offset.x,
+                y: offset.y + 1.0,
+            });
+        }
+    }
+
+    fn focusable(&self) -> bool {
+        !self.pages.is_empty()
+    }
+
+    fn set_focus(&self, focused: bool) {
+        self.focused.set(focused);
+    }
+
+    fn event(&self, size: Size, offset: Offset, _children: &[ViewTree], event: &Event) -> bool {
+        let bar = Rect::new(offset, Size::new(size.width, 1.0));
+        let on_bar = |x, y| bar.contains(x, y);
+        match *event {
+            Event::MousePress(MouseButton::Left, x, y) if on_bar(x, y) => {
+                self.click(size.width as usize, (x - offset.x) as usize);
+                true
+            }
+            Event::MousePress(MouseButton::WheelUp, x, y) if on_bar(x, y) => {
+                self.step(-1);
+                true
+            }
+            Event::MousePress(MouseButton::WheelDown, x, y) if on_bar(x, y) => {
+                self.step(1);
+                true
+            }
+            Event::KeyPress(KeyCode::PageUp, mods) if mods.ctrl => {
+                self.step(-1);
+                true
+            }
+            Event::KeyPress(KeyCode::PageDown, mods) if mods.ctrl => {
+                self.step(1);
+                true
+            }
+            Event::KeyPress(KeyCode::Left, mods) if mods.is_empty() && self.focused.get() => {
+                self.step(-1);
+                true
+            }
+            Event::KeyPress(KeyCode::Right, mods) if mods.is_empty() && self.focused.get() => {
+                self.step(1);
+                true
+            }
+            // Digits on the focused bar, or Alt-digit from anywhere in the
+            // page, so text inputs in the pages keep their digits.
+            Event::KeyPress(KeyCode::Char(c @ '1'..='9'), mods)
+                if (mods.is_empty() && self.focused.get())
+                    || (mods.alt && !mods.ctrl && !mods.shift) =>
+            {
+                let nth = c as usize - '1' as usize;
+                self.select(nth);
+                nth < self.pages.len()
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quill_derive::Hydrate;
+
+    // A page with state of its own.
+    #[derive(Debug, Clone, PartialEq, Hydrate)]
+    struct Page {
+        visits: State<u32>,
+    }
+
+    impl View for Page {
+        fn children(&self) -> Vec<AnyView> {
+            Vec::new()
+        }
+
+        fn layout(&self, _children: &[ViewTree], _constraint: Constraint) -> Size {
+            Size::new(10.0, 3.0)
+        }
+    }
+
+    fn tabs(selected: State<usize>) -> Tabs {
+        let page = || Page {
+            visits: State::new(0),
+        };
+        Tabs::new(selected)
+            .with_tab("one", page())
+            .with_tab("two", page())
+            .with_tab("three", page())
+    }
+
+    fn visits(tree: &ViewTree) -> State<u32> {
+        let page = &tree.children[0].view;
+        page.downcast_ref::<Page>().unwrap().visits.clone()
+    }
+
+    #[test]
+    fn pages_keep_their_state_when_hidden() {
+        let selected = State::new(0);
+        let mut tree = ViewTree::new(tabs(selected.clone()).any_view());
+        visits(&tree).set(5);
+
+        selected.set(1);
+        tree.perform_hydrate(tabs(selected.clone()).any_view());
+        assert_eq!(visits(&tree).get(), 0);
+        visits(&tree).set(2);
+
+        selected.set(0);
+        tree.perform_hydrate(tabs(selected.clone()).any_view());
+        assert_eq!(visits(&tree).get(), 5);
+        selected.set(1);
+        tree.perform_hydrate(tabs(selected.clone()).any_view());
+        assert_eq!(visits(&tree).get(), 2);
+    }
+
+    #[test]
+    fn spans_leave_room_for_the_arrows() {
+        // Tabs are 5, 5 and 7 columns wide, with separators between them.
+        let tabs = tabs(State::new(0));
+        assert_eq!(tabs.bar_width(), 19.0);
+        assert_eq!(
+            tabs.spans(19, 0),
+            (vec![(0, 0, 5), (1, 6, 5), (2, 12, 7)], false)
+        );
+        assert_eq!(tabs.spans(10, 0), (vec![(0, 0, 5)], true));
+        assert_eq!(tabs.spans(10, 1), (vec![(1, 1, 5)], true));
+        assert_eq!(tabs.spans(10, 2), (vec![(2, 1, 7)], false));
+        // A tab wider than the bar is cut.
+        assert_eq!(tabs.spans(3, 2), (vec![(2, 1, 2)], true));
+        assert_eq!(tabs.spans(0, 0), (vec![(0, 0, 0)], true));
+        assert_eq!(tabs.spans(0, 2), (vec![(2, 1, 0)], true));
+    }
+
+    #[test]
+    fn scroll_follows_the_selection() {
+        let selected = State::new(2);
+        let tabs = tabs(selected.clone());
+        tabs.fit_scroll(10);
+        assert_eq!(tabs.scroll.get(), 2);
+        selected.set(0);
+        tabs.fit_scroll(10);
+        assert_eq!(tabs.scroll.get(), 0);
+        // Without room for any tab, the selected one comes first.
+        selected.set(2);
+        tabs.fit_scroll(0);
+        assert_eq!(tabs.scroll.get(), 2);
+    }
+
+    #[test]
+    fn clicks_on_the_arrows_step() {
+        let selected = State::new(2);
+        let tabs = tabs(selected.clone());
+        tabs.fit_scroll(10);
+        tabs.click(10, 0);
+        assert_eq!(selected.get(), 1);
+        tabs.fit_scroll(10);
+        tabs.click(10, 9);
+        assert_eq!(selected.get(), 2);
+        tabs.scroll.set(0);
+        tabs.click(10, 3);
+        assert_eq!(selected.get(), 0);
+
+        // Nothing to click on an empty bar.
+        tabs.click(0, 0);
+        tabs.click(0, usize::MAX);
+        assert_eq!(selected.get(), 0);
+    }
+
+    #[test]
+    fn narrow_layout_keeps_the_selection_visible() {
+        let selected = State::new(2);
+        let tree = ViewTree::new(tabs(selected).any_view());
+        tree.layout(Constraint::new(0.0, 5.0));
+        let tabs = tree.view.downcast_ref::<Tabs>().unwrap().clone();
+        assert_eq!(tabs.scroll.get(), 2);
+        assert_eq!(tree.view.size.get(), Size::new(10.0, 4.0));
+    }
+}
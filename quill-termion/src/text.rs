@@ -0,0 +1,165 @@
+// Grapheme and word helpers shared by the text editing views. Positions are
+// byte offsets into the string and always sit on grapheme boundaries.
+
+use unicode_segmentation::{GraphemeCursor, UnicodeSegmentation};
+use unicode_width::UnicodeWidthStr;
+
+/// Number of terminal columns needed to display `s`.
+pub fn str_width(s: &str) -> usize {
+    UnicodeWidthStr::width(s)
+}
+
+/// Columns to draw for a view `width` wide, at most a screen's worth, so
+/// that an unbounded width is cut rather than filled.
+pub fn columns(width: f64) -> usize {
+    width.clamp(0.0, u16::MAX as f64) as usize
+}
+
+pub fn prev_grapheme(s: &str, idx: usize) -> usize {
+    let mut cursor = GraphemeCursor::new(idx, s.len(), true);
+    cursor.prev_boundary(s, 0).ok().flatten().unwrap_or(0)
+}
+
+pub fn next_grapheme(s: &str, idx: usize) -> usize {
+    let mut cursor = GraphemeCursor::new(idx, s.len(), true);
+    cursor.next_boundary(s, 0).ok().flatten().unwrap_or(s.len())
+}
+
+/// Snap `idx` to the closest grapheme boundary at or before it.
+pub fn clamp_boundary(s: &str, idx: usize) -> usize {
+    if idx >= s.len() {
+        return s.len();
+    }
+    let mut idx = idx;
+    while !s.is_char_boundary(idx) {
+        idx -= 1;
+    }
+    let mut cursor = GraphemeCursor::new(idx, s.len(), true);
+    match cursor.is_boundary(s, 0) {
+        Ok(true) => idx,
+        _ => prev_grapheme(s, idx),
+    }
+}
+
+fn is_word(grapheme: &str) -> bool {
+    grapheme
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// Start of the word before `idx`, skipping any separators first.
+pub fn prev_word(s: &str, idx: usize) -> usize {
+    let mut iter = s[..idx].grapheme_indices(true).rev().peekable();
+    while iter.next_if(|(_, g)| !is_word(g)).is_some() {}
+    let mut start = iter.peek().map_or(0, |(i, _)| *i);
+    while let Some((i, _)) = iter.next_if(|(_, g)| is_word(g)) {
+        start = i;
+    }
+    start
+}
+
+/// End of the word after `idx`, skipping any separators first.
+pub fn next_word(s: &str, idx: usize) -> usize {
+    let mut iter = s[idx..].grapheme_indices(true).peekable();
+    while iter.next_if(|(_, g)| !is_word(g)).is_some() {}
+    while iter.next_if(|(_, g)| is_word(g)).is_some() {}
+    iter.peek().map_or(s.len(), |(i, _)| idx + *i)
+}
+
+/// Display column of byte offset `idx`.
+pub fn column(s: &str, idx: usize) -> usize {
+    str_width(&s[..idx])
+}
+
+/// Byte offset of the grapheme covering display column `col`.
+pub fn offset_at_column(s: &str, col: usize) -> usize {
+    let mut width = 0;
+    for (idx, grapheme) in s.grapheme_indices(true) {
+        let w = str_width(grapheme);
+        if width + w > col {
+            return idx;
+        }
+        width += w;
+    }
+    s.len()
+}
+
+/// The part of `s` between display columns `from` and `from + width`. Wide
+/// graphemes cut by either edge are replaced by spaces so the result is
+/// exactly as wide as the visible part.
+pub fn slice_columns(s: &str, from: usize, width: usize) -> String {
+    let to = from + width;
+    let mut out = String::new();
+    let mut col = 0;
+    for grapheme in s.graphemes(true) {
+        let w = str_width(grapheme);
+        let end = col + w;
+        if end > from && col < to {
+            if col >= from && end <= to {
+                out.push_str(grapheme);
+            } else {
+                let visible = end.min(to) - col.max(from);
+                out.push_str(&" ".repeat(visible));
+            }
+        }
+        if end >= to {
+            break;
+        }
+        col = end;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn words_skip_separators_first() {
+        let s = "hello, world";
+        assert_eq!(prev_word(s, s.len()), 7);
+        assert_eq!(prev_word(s, 7), 0);
+        assert_eq!(prev_word(s, 3), 0);
+        assert_eq!(prev_word(s, 0), 0);
+        assert_eq!(next_word(s, 0), 5);
+        assert_eq!(next_word(s, 5), s.len());
+        assert_eq!(next_word(s, s.len()), s.len());
+        assert_eq!(next_word("  ", 0), 2);
+    }
+
+    #[test]
+    fn words_are_graphemes() {
+        // CJK characters are letters, emoji are separators.
+        let s = "你好 世界";
+        assert_eq!(next_word(s, 0), "你好".len());
+        assert_eq!(prev_word(s, s.len()), "你好 ".len());
+        let s = "ab 👍 cd";
+        assert_eq!(next_word(s, 2), s.len());
+        assert_eq!(prev_word(s, "ab 👍".len()), 0);
+        // A combining accent belongs to its letter.
+        let s = "cafe\u{301} noir";
+        assert_eq!(next_word(s, 0), "cafe\u{301}".len());
+        assert_eq!(prev_word(s, "cafe\u{301}".len()), 0);
+    }
+
+    #[test]
+    fn slice_columns_counts_display_width() {
+        assert_eq!(slice_columns("abc", 1, 10), "bc");
+        assert_eq!(slice_columns("abc", 5, 2), "");
+        assert_eq!(slice_columns("abc", 1, 0), "");
+        assert_eq!(slice_columns("你好世界", 2, 4), "好世");
+        assert_eq!(slice_columns("ab👍cd", 2, 2), "👍");
+    }
+
+    #[test]
+    fn slice_columns_pads_cut_wide_graphemes() {
+        // Both edges cut a character in half.
+        let s = slice_columns("你好世界", 1, 4);
+        assert_eq!(s, " 好 ");
+        assert_eq!(str_width(&s), 4);
+        assert_eq!(slice_columns("你好", 0, 3), "你 ");
+        assert_eq!(slice_columns("ab👍cd", 3, 2), " c");
+        assert_eq!(slice_columns("你", 0, 1), " ");
+    }
+}
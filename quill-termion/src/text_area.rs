@@ -0,0 +1,606 @@
+use crate::style::Style;
+use crate::text::*;
+use quill::*;
+use std::ops::Range;
+use termion::cursor;
+use unicode_segmentation::UnicodeSegmentation;
+
+const HISTORY_LIMIT: usize = 200;
+// Size taken without a `width` or `height` when the space is unbounded.
+const DEFAULT_WIDTH: f64 = 40.0;
+const DEFAULT_HEIGHT: f64 = 5.0;
+
+/// A visual row: a byte range of the text without the trailing newline. With
+/// soft wrap a logical line spans several rows.
+#[derive(Debug, Clone, Copy)]
+struct Row {
+    start: usize,
+    end: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Snapshot {
+    text: String,
+    cursor: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+struct History {
+    undo: Vec<Snapshot>,
+    redo: Vec<Snapshot>,
+    /// Whether the last edit was plain typing, so that consecutive
+    /// keystrokes are undone as one word.
+    typing: bool,
+}
+
+/// Multi-line text editor bound to a `State<String>`.
+///
+/// Tab inserts a tab (or spaces with `expand_tabs`), so focus can only leave
+/// the editor backwards with Shift-Tab.
+#[derive(Debug, Clone)]
+pub struct TextArea {
+    pub text: State<String>,
+    pub width: Option<f64>,
+    pub height: Option<f64>,
+    pub soft_wrap: bool,
+    pub tab_width: usize,
+    pub expand_tabs: bool,
+    cursor: State<usize>,
+    anchor: State<Option<usize>>,
+    goal: State<Option<usize>>,
+    scroll: State<(usize, usize)>,
+    follow: State<bool>,
+    dragging: State<bool>,
+    history: State<History>,
+    focused: State<bool>,
+}
+
+impl TextArea {
+    pub fn new(text: State<String>) -> Self {
+        TextArea {
+            text,
+            width: None,
+            height: None,
+            soft_wrap: false,
+            tab_width: 4,
+            expand_tabs: false,
+            cursor: State::new(0),
+            anchor: State::new(None),
+            goal: State::new(None),
+            scroll: State::new((0, 0)),
+            follow: State::new(true),
+            dragging: State::new(false),
+            history: State::new(History::default()),
+            focused: State::new(false),
+        }
+    }
+
+    pub fn width(mut self, width: f64) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    pub fn height(mut self, height: f64) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    pub fn soft_wrap(mut self, soft_wrap: bool) -> Self {
+        self.soft_wrap = soft_wrap;
+        self
+    }
+
+    pub fn tab_width(mut self, tab_width: usize) -> Self {
+        self.tab_width = tab_width.max(1);
+        self
+    }
+
+    pub fn expand_tabs(mut self, expand_tabs: bool) -> Self {
+        self.expand_tabs = expand_tabs;
+        self
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused.get()
+    }
+
+    /// The selected byte range, if any.
+    pub fn selection(&self) -> Option<Range<usize>> {
+        let cursor = self.cursor();
+        match self.anchor.get() {
+            Some(anchor) if anchor != cursor => {
+                let anchor = self.text.with(|text| clamp_boundary(text, anchor));
+                Some(anchor.min(cursor)..anchor.max(cursor))
+            }
+            _ => None,
+        }
+    }
+
+    fn cursor(&self) -> usize {
+        self.text
+            .with(|text| clamp_boundary(text, self.cursor.get()))
+    }
+
+    fn cell_width(&self, grapheme: &str, col: usize) -> usize {
+        if grapheme == "\t" {
+            self.tab_width - col % self.tab_width
+        } else {
+            str_width(grapheme)
+        }
+    }
+
+    fn rows(&self, text: &str, width: usize) -> Vec<Row> {
+        let mut rows = Vec::new();
+        let mut line_start = 0;
+        for line in text.split('\n') {
+            let end = line_start + line.len();
+            let mut start = line_start;
+            if self.soft_wrap {
+                let mut col = 0;
+                for (idx, grapheme) in line.grapheme_indices(true) {
+                    let mut w = self.cell_width(grapheme, col);
+                    if col > 0 && col + w > width {
+                        rows.push(Row {
+                            start,
+                            end: line_start + idx,
+                        });
+                        start = line_start + idx;
+                        col = 0;
+                        w = self.cell_width(grapheme, col);
+                    }
+                    col += w;
+                }
+                // A line that exactly fills the width gets an empty row for
+                // the cursor to sit on.
+                if col >= width && start < end {
+                    rows.push(Row { start, end });
+                    start = end;
+                }
+            }
+            rows.push(Row { start, end });
+            line_start = end + 1;
+        }
+        rows
+    }
+
+    /// Graphemes of a row as `(byte offset, grapheme, column, width)`.
+    fn cells<'a>(&self, text: &'a str, row: Row) -> Vec<(usize, &'a str, usize, usize)> {
+        let mut col = 0;
+        text[row.start..row.end]
+            .grapheme_indices(true)
+            .map(|(idx, grapheme)| {
+                let w = self.cell_width(grapheme, col);
+                col += w;
+                (row.start + idx, grapheme, col - w, w)
+            })
+            .collect()
+    }
+
+    fn row_of(rows: &[Row], pos: usize) -> usize {
+        rows.iter().rposition(|row| row.start <= pos).unwrap_or(0)
+    }
+
+    fn column_of(&self, text: &str, row: Row, pos: usize) -> usize {
+        self.cells(text, row)
+            .iter()
+            .take_while(|(idx, ..)| *idx < pos)
+            .map(|(_, _, _, w)| w)
+            .sum()
+    }
+
+    /// Byte offset closest to `col` in row `nth`.
+    fn offset_at(&self, text: &str, rows: &[Row], nth: usize, col: usize) -> usize {
+        let row = rows[nth];
+        for (idx, _, start, w) in self.cells(text, row) {
+            if start + w > col {
+                return idx;
+            }
+        }
+        // The end of a wrapped row is the start of the next one, so stay on
+        // the last grapheme instead.
+        match rows.get(nth + 1) {
+            Some(next) if next.start == row.end && row.end > row.start => {
+                prev_grapheme(text, row.end)
+            }
+            _ => row.end,
+        }
+    }
+
+    fn wrap_width(&self, size: Size) -> usize {
+        columns(size.width).max(1)
+    }
+
+    fn move_cursor(&self, to: usize, select: bool) {
+        let cursor = self.cursor();
+        if select {
+            if self.anchor.get().is_none() {
+                self.anchor.set(Some(cursor));
+            }
+        } else {
+            self.anchor.set(None);
+        }
+        self.cursor.set(to);
+        self.follow.set(true);
+    }
+
+    fn move_vertical(&self, size: Size, delta: isize, select: bool) {
+        let cursor = self.cursor();
+        let to = self.text.with(|text| {
+            let rows = self.rows(text, self.wrap_width(size));
+            let current = Self::row_of(&rows, cursor);
+            let goal = self
+                .goal
+                .get()
+                .unwrap_or_else(|| self.column_of(text, rows[current], cursor));
+            self.goal.set(Some(goal));
+            let target = (current as isize + delta).clamp(0, rows.len() as isize - 1) as usize;
+            if target == current && delta < 0 {
+                0
+            } else if target == current && delta > 0 {
+                text.len()
+            } else {
+                self.offset_at(text, &rows, target, goal)
+            }
+        });
+        self.move_cursor(to, select);
+    }
+
+    fn move_horizontal(&self, f: impl FnOnce(&str, usize) -> usize, select: bool) {
+        let cursor = self.cursor();
+        let to = self.text.with(|text| f(text, cursor));
+        self.goal.set(None);
+        self.move_cursor(to, select);
+    }
+
+    fn row_edge(&self, size: Size, end: bool, select: bool) {
+        let cursor = self.cursor();
+        let to = self.text.with(|text| {
+            let rows = self.rows(text, self.wrap_width(size));
+            let nth = Self::row_of(&rows, cursor);
+            if end {
+                self.offset_at(text, &rows, nth, usize::MAX)
+            } else {
+                rows[nth].start
+            }
+        });
+        self.goal.set(None);
+        self.move_cursor(to, select);
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            text: self.text.get(),
+            cursor: self.cursor(),
+        }
+    }
+
+    fn restore(&self, snapshot: Snapshot) {
+        self.text.set(snapshot.text);
+        self.cursor.set(snapshot.cursor);
+        self.anchor.set(None);
+        self.goal.set(None);
+        self.follow.set(true);
+    }
+
+    /// Replace `range` with `insert`, recording an undo step.
+    fn replace(&self, range: Range<usize>, insert: &str, typing: bool) {
+        let snapshot = self.snapshot();
+        self.history.update(|history| {
+            if !(typing && history.typing) {
+                history.undo.push(snapshot);
+                if history.undo.len() > HISTORY_LIMIT {
+                    history.undo.remove(0);
+                }
+            }
+            history.redo.clear();
+            history.typing = typing;
+        });
+        self.text
+            .update(|text| text.replace_range(range.clone(), insert));
+        self.cursor.set(range.start + insert.len());
+        self.anchor.set(None);
+        self.goal.set(None);
+        self.follow.set(true);
+    }
+
+    fn insert(&self, insert: &str, typing: bool) {
+        let cursor = self.cursor();
+        let range = self.selection().unwrap_or(cursor..cursor);
+        self.replace(range, insert, typing);
+    }
+
+    fn delete(&self, f: impl FnOnce(&str, usize) -> usize) {
+        let range = match self.selection() {
+            Some(sel) => sel,
+            None => {
+                let cursor = self.cursor();
+                let to = self.text.with(|text| f(text, cursor));
+                to.min(cursor)..to.max(cursor)
+            }
+        };
+        if !range.is_empty() {
+            self.replace(range, "", false);
+        }
+    }
+
+    pub fn undo(&self) {
+        let current = self.snapshot();
+        let prev = self.history.update(|history| {
+            history.typing = false;
+            let prev = history.undo.pop()?;
+            history.redo.push(current);
+            Some(prev)
+        });
+        if let Some(prev) = prev {
+            self.restore(prev);
+        }
+    }
+
+    pub fn redo(&self) {
+        let current = self.snapshot();
+        let next = self.history.update(|history| {
+            history.typing = false;
+            let next = history.redo.pop()?;
+            history.undo.push(current);
+            Some(next)
+        });
+        if let Some(next) = next {
+            self.restore(next);
+        }
+    }
+
+    fn key_press(&self, size: Size, key: KeyCode, mods: Modifiers) -> bool {
+        let word = mods.ctrl || mods.alt;
+        let page = columns(size.height).max(1) as isize;
+        match key {
+            KeyCode::Char(c) if mods.ctrl => match c {
+                'z' => self.undo(),
+                'y' => self.redo(),
+                'a' => {
+                    let end = self.text.with(|text| text.len());
+                    self.anchor.set(Some(0));
+                    self.cursor.set(end);
+                }
+                _ => return false,
+            },
+            KeyCode::Char(c) if !mods.alt && !c.is_control() => {
+                self.insert(&c.to_string(), !c.is_whitespace())
+            }
+            KeyCode::Enter => self.insert("\n", false),
+            KeyCode::Tab if self.expand_tabs => {
+                let cursor = self.cursor();
+                let col = self.text.with(|text| {
+                    let rows = self.rows(text, self.wrap_width(size));
+                    self.column_of(text, rows[Self::row_of(&rows, cursor)], cursor)
+                });
+                let spaces = self.tab_width - col % self.tab_width;
+                self.insert(&" ".repeat(spaces), false)
+            }
+            KeyCode::Tab => self.insert("\t", false),
+            KeyCode::Backspace if word => self.delete(prev_word),
+            KeyCode::Backspace => self.delete(prev_grapheme),
+            KeyCode::Delete if word => self.delete(next_word),
+            KeyCode::Delete => self.delete(next_grapheme),
+            KeyCode::Left if word => self.move_horizontal(prev_word, mods.shift),
+            KeyCode::Right if word => self.move_horizontal(next_word, mods.shift),
+            KeyCode::Left => self.move_horizontal(prev_grapheme, mods.shift),
+            KeyCode::Right => self.move_horizontal(next_grapheme, mods.shift),
+            KeyCode::Up => self.move_vertical(size, -1, mods.shift),
+            KeyCode::Down => self.move_vertical(size, 1, mods.shift),
+            KeyCode::PageUp if !mods.ctrl => self.move_vertical(size, -page, mods.shift),
+            KeyCode::PageDown if !mods.ctrl => self.move_vertical(size, page, mods.shift),
+            KeyCode::Home if mods.ctrl => self.move_horizontal(|_, _| 0, mods.shift),
+            KeyCode::End if mods.ctrl => self.move_horizontal(|text, _| text.len(), mods.shift),
+            KeyCode::Home => self.row_edge(size, false, mods.shift),
+            KeyCode::End => self.row_edge(size, true, mods.shift),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Byte offset under a screen position. Points outside the editor are
+    /// clamped, which lets a drag past the edge extend the selection.
+    fn position_at(&self, size: Size, offset: Offset, x: f64, y: f64) -> usize {
+        let (srow, scol) = self.scroll.get();
+        self.text.with(|text| {
+            let rows = self.rows(text, self.wrap_width(size));
+            let nth = (srow as f64 + (y - offset.y).floor()).clamp(0.0, rows.len() as f64 - 1.0);
+            let col = scol + (x - offset.x).max(0.0) as usize;
+            self.offset_at(text, &rows, nth as usize, col)
+        })
+    }
+
+    /// Draw the visible rows and return the caret position when the editor
+    /// has focus.
+    pub fn render(
+        &self,
+        size: Size,
+        offset: Offset,
+        screen: &mut impl std::io::Write,
+    ) -> Option<Offset> {
+        let width = columns(size.width).max(1);
+        let height = columns(size.height).max(1);
+        let text = self.text.get();
+        let rows = self.rows(&text, width);
+        let cursor = clamp_boundary(&text, self.cursor.get());
+        let crow = Self::row_of(&rows, cursor);
+        let ccol = self.column_of(&text, rows[crow], cursor);
+
+        let (mut srow, mut scol) = self.scroll.get();
+        if self.follow.get() {
+            if crow < srow {
+                srow = crow;
+            } else if crow >= srow + height {
+                srow = crow + 1 - height;
+            }
+            if ccol < scol {
+                scol = ccol;
+            } else if ccol >= scol + width {
+                scol = ccol + 1 - width;
+            }
+            self.follow.set(false);
+        }
+        srow = srow.min(rows.len().saturating_sub(1));
+        if self.soft_wrap {
+            scol = 0;
+        }
+        if (srow, scol) != self.scroll.get() {
+            self.scroll.set((srow, scol));
+        }
+
+        let selection = self.selection();
+        let plain = Style::new();
+        let selected = Style::new().reverse();
+        for line in 0..height {
+            write!(
+                screen,
+                "{}{}",
+                cursor::Goto(offset.x as u16, (offset.y as usize + line) as u16),
+                plain
+            )
+            .unwrap();
+            let mut used = 0;
+            if let Some(row) = rows.get(srow + line) {
+                let mut current = plain;
+                for (idx, grapheme, col, w) in self.cells(&text, *row) {
+                    let end = col + w;
+                    if end <= scol {
+                        continue;
+                    }
+                    if col >= scol + width {
+                        break;
+                    }
+                    let style = match &selection {
+                        Some(sel) if sel.contains(&idx) => selected,
+                        _ => plain,
+                    };
+                    if style != current {
+                        write!(screen, "{}", style).unwrap();
+                        current = style;
+                    }
+                    let visible = end.min(scol + width) - col.max(scol);
+                    if grapheme == "\t" || visible < w {
+                        write!(screen, "{}", " ".repeat(visible)).unwrap();
+                    } else {
+                        write!(screen, "{}", grapheme).unwrap();
+                    }
+                    used += visible;
+                }
+                if current != plain {
+                    write!(screen, "{}", plain).unwrap();
+                }
+            }
+            write!(screen, "{}", " ".repeat(width.saturating_sub(used))).unwrap();
+        }
+        write!(screen, "{}", termion::style::Reset).unwrap();
+
+        if self.focused.get() && crow >= srow && crow < srow + height {
+            Some(Offset {
+                x: offset.x + (ccol - scol) as f64,
+                y: offset.y + (crow - srow) as f64,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+impl Hydrate for TextArea {
+    fn hydrate(&mut self, other: &Self) {
+        self.text = other.text.clone();
+        self.width = other.width;
+        self.height = other.height;
+        self.soft_wrap = other.soft_wrap;
+        self.tab_width = other.tab_width;
+        self.expand_tabs = other.expand_tabs;
+    }
+}
+
+impl View for TextArea {
+    fn children(&self) -> Vec<AnyView> {
+        Vec::new()
+    }
+
+    fn layout(&self, _children: &[ViewTree], constraint: Constraint) -> Size {
+        Size {
+            width: self
+                .width
+                .unwrap_or(bounded(constraint.max_width(), DEFAULT_WIDTH))
+                .max(1.0),
+            height: self
+                .height
+                .unwrap_or(bounded(constraint.max_height(), DEFAULT_HEIGHT))
+                .max(1.0),
+        }
+    }
+
+    fn focusable(&self) -> bool {
+        true
+    }
+
+    fn set_focus(&self, focused: bool) {
+        self.focused.set(focused);
+    }
+
+    fn event(&self, size: Size, offset: Offset, _children: &[ViewTree], event: &Event) -> bool {
+        let bounds = Rect::new(offset, size);
+        match *event {
+            Event::MousePress(MouseButton::Left, x, y) if bounds.contains(x, y) => {
+                let pos = self.position_at(size, offset, x, y);
+                self.cursor.set(pos);
+                self.anchor.set(Some(pos));
+                self.goal.set(None);
+                self.dragging.set(true);
+                self.follow.set(true);
+                true
+            }
+            Event::MousePress(MouseButton::WheelUp, x, y) if bounds.contains(x, y) => {
+                let (srow, scol) = self.scroll.get();
+                self.scroll.set((srow.saturating_sub(3), scol));
+                true
+            }
+            Event::MousePress(MouseButton::WheelDown, x, y) if bounds.contains(x, y) => {
+                let (srow, scol) = self.scroll.get();
+                self.scroll.set((srow + 3, scol));
+                true
+            }
+            Event::MouseHold(x, y) if self.dragging.get() => {
+                self.cursor.set(self.position_at(size, offset, x, y));
+                self.follow.set(true);
+                true
+            }
+            Event::MouseRelease(..) if self.dragging.get() => {
+                self.dragging.set(false);
+                if self.anchor.get() == Some(self.cursor()) {
+                    self.anchor.set(None);
+                }
+                true
+            }
+            Event::KeyPress(key, mods) => self.key_press(size, key, mods),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unbounded_size_falls_back_to_the_default() {
+        let area = TextArea::new(State::new(String::from("one\ntwo")));
+        let tree = ViewTree::new(area.clone().any_view());
+        tree.layout(Constraint::unbounded());
+        let size = tree.view.size.get();
+        assert_eq!(size, Size::new(DEFAULT_WIDTH, DEFAULT_HEIGHT));
+
+        let mut screen = Vec::new();
+        area.render(size, Offset::new(1.0, 1.0), &mut screen);
+        // Drawing an unbounded width is cut to a screen.
+        area.render(
+            Size::new(f64::INFINITY, 2.0),
+            Offset::new(1.0, 1.0),
+            &mut screen,
+        );
+    }
+}
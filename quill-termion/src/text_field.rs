@@ -0,0 +1,306 @@
+use crate::style::Style;
+use crate::text::*;
+use quill::*;
+use std::rc::Rc;
+use termion::cursor;
+use unicode_segmentation::UnicodeSegmentation;
+
+type SubmitFn = Rc<dyn Fn(&str)>;
+
+// Width taken without a `width` when the space is unbounded.
+const DEFAULT_WIDTH: f64 = 20.0;
+
+/// Single-line text input bound to a `State<String>`.
+#[derive(Clone)]
+pub struct TextField {
+    pub text: State<String>,
+    pub placeholder: String,
+    pub mask: Option<char>,
+    pub width: Option<f64>,
+    pub on_submit: Option<SubmitFn>,
+    cursor: State<usize>,
+    scroll: State<usize>,
+    focused: State<bool>,
+}
+
+impl std::fmt::Debug for TextField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TextField")
+            .field("text", &self.text)
+            .field("cursor", &self.cursor)
+            .field("focused", &self.focused)
+            .finish()
+    }
+}
+
+impl TextField {
+    pub fn new(text: State<String>) -> Self {
+        let end = text.with(|text| text.len());
+        TextField {
+            text,
+            placeholder: String::new(),
+            mask: None,
+            width: None,
+            on_submit: None,
+            cursor: State::new(end),
+            scroll: State::new(0),
+            focused: State::new(false),
+        }
+    }
+
+    pub fn placeholder(mut self, placeholder: impl ToString) -> Self {
+        self.placeholder = placeholder.to_string();
+        self
+    }
+
+    /// Hide the input behind bullets.
+    pub fn password(self) -> Self {
+        self.mask('•')
+    }
+
+    pub fn mask(mut self, mask: char) -> Self {
+        self.mask = Some(mask);
+        self
+    }
+
+    pub fn width(mut self, width: f64) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    pub fn on_submit(mut self, cb: impl Fn(&str) + 'static) -> Self {
+        self.on_submit = Some(Rc::new(cb));
+        self
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused.get()
+    }
+
+    /// Cursor position as a byte offset, snapped to the current text.
+    fn cursor(&self) -> usize {
+        self.text
+            .with(|text| clamp_boundary(text, self.cursor.get()))
+    }
+
+    fn display(&self) -> String {
+        match self.mask {
+            Some(mask) => self
+                .text
+                .with(|text| text.graphemes(true).map(|_| mask).collect()),
+            None => self.text.get(),
+        }
+    }
+
+    /// Display column of the cursor, taking the mask into account.
+    fn caret_column(&self) -> usize {
+        let cursor = self.cursor();
+        self.text.with(|text| match self.mask {
+            Some(mask) => text[..cursor].graphemes(true).count() * str_width(&mask.to_string()),
+            None => column(text, cursor),
+        })
+    }
+
+    fn edit(&self, f: impl FnOnce(&mut String, usize) -> usize) {
+        let cursor = self.cursor();
+        let cursor = self.text.update(|text| f(text, cursor));
+        self.cursor.set(cursor);
+    }
+
+    fn move_to(&self, f: impl FnOnce(&str, usize) -> usize) {
+        let cursor = self.cursor();
+        self.cursor.set(self.text.with(|text| f(text, cursor)));
+    }
+
+    fn key_press(&self, key: KeyCode, mods: Modifiers) -> bool {
+        let word = mods.ctrl || mods.alt;
+        match key {
+            KeyCode::Char(c) if mods.ctrl => match c {
+                'a' => self.move_to(|_, _| 0),
+                'e' => self.move_to(|text, _| text.len()),
+                'w' => self.edit(|text, cursor| {
+                    let start = prev_word(text, cursor);
+                    text.replace_range(start..cursor, "");
+                    start
+                }),
+                'u' => self.edit(|text, cursor| {
+                    text.replace_range(..cursor, "");
+                    0
+                }),
+                'k' => self.edit(|text, cursor| {
+                    text.truncate(cursor);
+                    cursor
+                }),
+                _ => return false,
+            },
+            KeyCode::Char(c) if !mods.alt && !c.is_control() => self.edit(|text, cursor| {
+                text.insert(cursor, c);
+                cursor + c.len_utf8()
+            }),
+            KeyCode::Backspace => self.edit(|text, cursor| {
+                let start = if word {
+                    prev_word(text, cursor)
+                } else {
+                    prev_grapheme(text, cursor)
+                };
+                text.replace_range(start..cursor, "");
+                start
+            }),
+            KeyCode::Delete => self.edit(|text, cursor| {
+                let end = if word {
+                    next_word(text, cursor)
+                } else {
+                    next_grapheme(text, cursor)
+                };
+                text.replace_range(cursor..end, "");
+                cursor
+            }),
+            KeyCode::Left if word => self.move_to(prev_word),
+            KeyCode::Right if word => self.move_to(next_word),
+            KeyCode::Left => self.move_to(prev_grapheme),
+            KeyCode::Right => self.move_to(next_grapheme),
+            KeyCode::Home => self.move_to(|_, _| 0),
+            KeyCode::End => self.move_to(|text, _| text.len()),
+            KeyCode::Enter => match &self.on_submit {
+                Some(cb) => self.text.with(|text| cb(text)),
+                None => return false,
+            },
+            _ => return false,
+        }
+        true
+    }
+
+    /// Draw the visible part of the text and return the caret position when
+    /// the field has focus.
+    pub fn render(
+        &self,
+        size: Size,
+        offset: Offset,
+        screen: &mut impl std::io::Write,
+    ) -> Option<Offset> {
+        let width = columns(size.width).max(1);
+        let caret = self.caret_column();
+        let mut scroll = self.scroll.get();
+        if caret < scroll {
+            scroll = caret;
+        } else if caret >= scroll + width {
+            scroll = caret + 1 - width;
+        }
+        if scroll != self.scroll.get() {
+            self.scroll.set(scroll);
+        }
+
+        let empty = self.text.with(|text| text.is_empty());
+        let (line, style) = if empty {
+            (
+                slice_columns(&self.placeholder, 0, width),
+                Style::new().underline().dim(),
+            )
+        } else {
+            (
+                slice_columns(&self.display(), scroll, width),
+                Style::new().underline(),
+            )
+        };
+        let pad = width.saturating_sub(str_width(&line));
+        write!(
+            screen,
+            "{}{}{}{}{}",
+            cursor::Goto(offset.x as u16, offset.y as u16),
+            style,
+            line,
+            " ".repeat(pad),
+            termion::style::Reset
+        )
+        .unwrap();
+
+        if self.focused.get() {
+            Some(Offset {
+                x: offset.x + (caret - scroll) as f64,
+                y: offset.y,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+impl Hydrate for TextField {
+    fn hydrate(&mut self, other: &Self) {
+        self.text = other.text.clone();
+        self.placeholder = other.placeholder.clone();
+        self.mask = other.mask;
+        self.width = other.width;
+        self.on_submit = other.on_submit.clone();
+    }
+}
+
+impl View for TextField {
+    fn children(&self) -> Vec<AnyView> {
+        Vec::new()
+    }
+
+    fn layout(&self, _children: &[ViewTree], constraint: Constraint) -> Size {
+        Size {
+            width: self
+                .width
+                .unwrap_or(bounded(constraint.max_width(), DEFAULT_WIDTH))
+                .max(1.0),
+            height: 1.0,
+        }
+    }
+
+    fn focusable(&self) -> bool {
+        true
+    }
+
+    fn set_focus(&self, focused: bool) {
+        self.focused.set(focused);
+    }
+
+    fn event(&self, size: Size, offset: Offset, _children: &[ViewTree], event: &Event) -> bool {
+        match *event {
+            Event::MousePress(MouseButton::Left, x, y)
+                if Rect::new(offset, size).contains(x, y) =>
+            {
+                let col = (x - offset.x) as usize + self.scroll.get();
+                let display = self.display();
+                let idx = offset_at_column(&display, col);
+                // Map the position in the (possibly masked) display string
+                // back onto the text.
+                let nth = display[..idx].graphemes(true).count();
+                self.cursor.set(self.text.with(|text| {
+                    text.grapheme_indices(true)
+                        .nth(nth)
+                        .map_or(text.len(), |(i, _)| i)
+                }));
+                true
+            }
+            Event::KeyPress(key, mods) => self.key_press(key, mods),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unbounded_width_falls_back_to_the_default() {
+        let field = TextField::new(State::new(String::from("hi")));
+        let tree = ViewTree::new(field.clone().any_view());
+        tree.layout(Constraint::unbounded());
+        let size = tree.view.size.get();
+        assert_eq!(size, Size::new(DEFAULT_WIDTH, 1.0));
+
+        let mut screen = Vec::new();
+        field.render(size, Offset::new(1.0, 1.0), &mut screen);
+        // Drawing an unbounded width is cut to a screen.
+        field.render(
+            Size::new(f64::INFINITY, 1.0),
+            Offset::new(1.0, 1.0),
+            &mut screen,
+        );
+    }
+}
@@ -0,0 +1,577 @@
+use crate::style::Style;
+use crate::text::{slice_columns, str_width};
+use quill::*;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use termion::cursor;
+
+// Seconds between the presses of a double-click, on the frame clock.
+const DOUBLE_CLICK: f64 = 0.4;
+
+/// The ids from a root node down to a node.
+pub type TreePath = Vec<String>;
+
+type LoadFn = Rc<dyn Fn() -> Vec<TreeNode>>;
+type ActivateFn = Rc<dyn Fn(&[String])>;
+
+#[derive(Clone)]
+pub enum TreeChildren {
+    Leaf,
+    Loaded(Vec<TreeNode>),
+    /// Loaded the first time the node is expanded.
+    Lazy(LoadFn),
+}
+
+impl std::fmt::Debug for TreeChildren {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TreeChildren::Leaf => write!(f, "Leaf"),
+            TreeChildren::Loaded(nodes) => f.debug_tuple("Loaded").field(nodes).finish(),
+            TreeChildren::Lazy(_) => write!(f, "Lazy"),
+        }
+    }
+}
+
+/// A node of a `TreeView`. Nodes are identified by their id, which defaults
+/// to the label and must be unique among siblings.
+#[derive(Debug, Clone)]
+pub struct TreeNode {
+    pub id: String,
+    pub label: String,
+    pub children: TreeChildren,
+}
+
+impl TreeNode {
+    pub fn new(label: impl ToString) -> Self {
+        let label = label.to_string();
+        TreeNode {
+            id: label.clone(),
+            label,
+            children: TreeChildren::Leaf,
+        }
+    }
+
+    pub fn id(mut self, id: impl ToString) -> Self {
+        self.id = id.to_string();
+        self
+    }
+
+    pub fn with_child(mut self, child: TreeNode) -> Self {
+        match &mut self.children {
+            TreeChildren::Loaded(children) => children.push(child),
+            _ => self.children = TreeChildren::Loaded(vec![child]),
+        }
+        self
+    }
+
+    pub fn with_children(mut self, children: impl IntoIterator<Item = TreeNode>) -> Self {
+        for child in children {
+            self = self.with_child(child);
+        }
+        self
+    }
+
+    /// Produce the children on first expansion. The result is kept for the
+    /// lifetime of the view, so `load` runs at most once per node.
+    pub fn lazy(mut self, load: impl Fn() -> Vec<TreeNode> + 'static) -> Self {
+        self.children = TreeChildren::Lazy(Rc::new(load));
+        self
+    }
+
+    pub fn is_branch(&self) -> bool {
+        !matches!(self.children, TreeChildren::Leaf)
+    }
+}
+
+// One visible line of the tree.
+struct Row {
+    path: TreePath,
+    label: String,
+    guides: String,
+    marker: &'static str,
+    branch: bool,
+    expanded: bool,
+}
+
+/// A collapsible tree bound to the path of the selected node.
+///
+/// The selection and the set of expanded nodes are kept as paths of node ids,
+/// so they survive the tree being rebuilt by `body`. If the selected node is
+/// hidden or removed, its closest visible ancestor is selected instead.
+#[derive(Clone)]
+pub struct TreeView {
+    pub roots: Vec<TreeNode>,
+    pub selected: State<TreePath>,
+    pub expanded: Option<State<HashSet<TreePath>>>,
+    pub height: Option<f64>,
+    pub guides: bool,
+    pub highlight: Style,
+    pub on_activate: Option<ActivateFn>,
+    // Expanded nodes when `expanded` isn't bound by the parent.
+    open: State<HashSet<TreePath>>,
+    loaded: State<HashMap<TreePath, Rc<Vec<TreeNode>>>>,
+    scroll: State<usize>,
+    follow: State<bool>,
+    last_click: State<Option<(usize, f64)>>,
+    focused: State<bool>,
+}
+
+impl std::fmt::Debug for TreeView {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TreeView")
+            .field("roots", &self.roots.len())
+            .field("selected", &self.selected)
+            .field("expanded", self.expanded_nodes())
+            .field("scroll", &self.scroll)
+            .field("focused", &self.focused)
+            .finish()
+    }
+}
+
+impl TreeView {
+    pub fn new(selected: State<TreePath>) -> Self {
+        TreeView {
+            roots: Vec::new(),
+            selected,
+            expanded: None,
+            height: None,
+            guides: true,
+            highlight: Style::new().reverse(),
+            on_activate: None,
+            open: State::new(HashSet::new()),
+            loaded: State::new(HashMap::new()),
+            scroll: State::new(0),
+            follow: State::new(true),
+            last_click: State::new(None),
+            focused: State::new(false),
+        }
+    }
+
+    pub fn with_root(mut self, root: TreeNode) -> Self {
+        self.roots.push(root);
+        self
+    }
+
+    pub fn with_roots(mut self, roots: impl IntoIterator<Item = TreeNode>) -> Self {
+        self.roots.extend(roots);
+        self
+    }
+
+    /// Bind the set of expanded nodes to a state owned by the parent.
+    pub fn expanded(mut self, expanded: State<HashSet<TreePath>>) -> Self {
+        self.expanded = Some(expanded);
+        self
+    }
+
+    pub fn height(mut self, height: f64) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    /// Indent with plain spaces instead of box-drawing guides.
+    pub fn without_guides(mut self) -> Self {
+        self.guides = false;
+        self
+    }
+
+    pub fn highlight(mut self, style: Style) -> Self {
+        self.highlight = style;
+        self
+    }
+
+    /// Called with the path of a leaf on Enter or double-click.
+    pub fn on_activate(mut self, cb: impl Fn(&[String]) + 'static) -> Self {
+        self.on_activate = Some(Rc::new(cb));
+        self
+    }
+
+    fn expanded_nodes(&self) -> &State<HashSet<TreePath>> {
+        self.expanded.as_ref().unwrap_or(&self.open)
+    }
+
+    fn rows(&self) -> Vec<Row> {
+        let mut rows = Vec::new();
+        let expanded = self.expanded_nodes().get();
+        self.flatten(&self.roots, &mut Vec::new(), "", &expanded, &mut rows);
+        rows
+    }
+
+    fn flatten(
+        &self,
+        nodes: &[TreeNode],
+        path: &mut TreePath,
+        prefix: &str,
+        expanded: &HashSet<TreePath>,
+        rows: &mut Vec<Row>,
+    ) {
+        for (nth, node) in nodes.iter().enumerate() {
+            let last = nth + 1 == nodes.len();
+            path.push(node.id.clone());
+            let (connector, indent) = match (path.len(), self.guides, last) {
+                (1, _, _) => ("", ""),
+                (_, true, false) => ("├─", "│  "),
+                (_, true, true) => ("└─", "   "),
+                (_, false, _) => ("  ", "   "),
+            };
+            let open = node.is_branch() && expanded.contains(path);
+            let marker = match (node.is_branch(), open) {
+                (true, true) => "▾ ",
+                (true, false) => "▸ ",
+                (false, _) if self.guides && path.len() > 1 => "─ ",
+                (false, _) => "  ",
+            };
+            rows.push(Row {
+                path: path.clone(),
+                label: node.label.clone(),
+                guides: format!("{}{}", prefix, connector),
+                marker,
+                branch: node.is_branch(),
+                expanded: open,
+            });
+            if open {
+                let prefix = format!("{}{}", prefix, indent);
+                match &node.children {
+                    TreeChildren::Leaf => {}
+                    TreeChildren::Loaded(children) => {
+                        self.flatten(children, path, &prefix, expanded, rows)
+                    }
+                    TreeChildren::Lazy(load) => {
+                        let children = self.load(path, load);
+                        self.flatten(&children, path, &prefix, expanded, rows)
+                    }
+                }
+            }
+            path.pop();
+        }
+    }
+
+    fn load(&self, path: &TreePath, load: &LoadFn) -> Rc<Vec<TreeNode>> {
+        if let Some(children) = self.loaded.with(|loaded| loaded.get(path).cloned()) {
+            return children;
+        }
+        let children = Rc::new(load());
+        self.loaded
+            .update(|loaded| loaded.insert(path.clone(), children.clone()));
+        children
+    }
+
+    /// The row showing the selection: the selected node, or its deepest
+    /// visible ancestor.
+    fn selected_row(&self, rows: &[Row]) -> usize {
+        let selected = self.selected.get();
+        rows.iter()
+            .enumerate()
+            .filter(|(_, row)| selected.starts_with(&row.path))
+            .max_by_key(|(_, row)| row.path.len())
+            .map_or(0, |(nth, _)| nth)
+    }
+
+    fn select(&self, rows: &[Row], nth: usize) {
+        if let Some(row) = rows.get(nth.min(rows.len().saturating_sub(1))) {
+            self.selected.set(row.path.clone());
+            self.follow.set(true);
+        }
+    }
+
+    fn set_expanded(&self, path: &TreePath, open: bool) {
+        if open {
+            self.expanded_nodes()
+                .update(|expanded| expanded.insert(path.clone()));
+        } else {
+            self.expanded_nodes()
+                .update(|expanded| expanded.remove(path));
+            if self.selected.with(|selected| selected.starts_with(path)) {
+                self.selected.set(path.clone());
+            }
+        }
+        self.follow.set(true);
+    }
+
+    fn toggle(&self, row: &Row) {
+        if row.branch {
+            self.set_expanded(&row.path, !row.expanded);
+        }
+    }
+
+    fn activate(&self, row: &Row) -> bool {
+        if row.branch {
+            self.toggle(row);
+            return true;
+        }
+        match &self.on_activate {
+            Some(cb) => {
+                cb(&row.path);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn key_press(&self, size: Size, key: KeyCode) -> bool {
+        let rows = self.rows();
+        if rows.is_empty() {
+            return false;
+        }
+        let nth = self.selected_row(&rows);
+        let row = &rows[nth];
+        let page = (size.height as usize).max(1);
+        match key {
+            KeyCode::Up => self.select(&rows, nth.saturating_sub(1)),
+            KeyCode::Down => self.select(&rows, nth + 1),
+            KeyCode::PageUp => self.select(&rows, nth.saturating_sub(page)),
+            KeyCode::PageDown => self.select(&rows, nth + page),
+            KeyCode::Home => self.select(&rows, 0),
+            KeyCode::End => self.select(&rows, rows.len() - 1),
+            KeyCode::Right if row.branch && !row.expanded => self.set_expanded(&row.path, true),
+            KeyCode::Right if row.expanded => self.select(&rows, nth + 1),
+            KeyCode::Left if row.expanded => self.set_expanded(&row.path, false),
+            KeyCode::Left if row.path.len() > 1 => {
+                self.selected.set(row.path[..row.path.len() - 1].to_vec());
+                self.follow.set(true);
+            }
+            KeyCode::Char(' ') if row.branch => self.toggle(row),
+            KeyCode::Enter => return self.activate(row),
+            _ => return false,
+        }
+        true
+    }
+
+    fn click(&self, offset: Offset, x: f64, y: f64) {
+        let rows = self.rows();
+        let nth = self.scroll.get() + (y - offset.y) as usize;
+        let Some(row) = rows.get(nth) else {
+            return;
+        };
+        let marker = offset.x + str_width(&row.guides) as f64;
+        if row.branch && x >= marker && x < marker + 2.0 {
+            self.selected.set(row.path.clone());
+            self.toggle(row);
+            self.last_click.set(None);
+            return;
+        }
+        self.select(&rows, nth);
+        let now = quill::frame_time();
+        let double = matches!(self.last_click.get(),
+            Some((prev, at)) if prev == nth && now - at < DOUBLE_CLICK);
+        if double {
+            self.last_click.set(None);
+            self.activate(row);
+        } else {
+            self.last_click.set(Some((nth, now)));
+        }
+    }
+
+    /// Draw the visible rows, scrolling to keep the selection in view.
+    pub fn render(&self, size: Size, offset: Offset, screen: &mut impl std::io::Write) {
+        let width = size.width.max(0.0) as usize;
+        let height = size.height.max(0.0) as usize;
+        let rows = self.rows();
+        let selected = self.selected_row(&rows);
+        let mut scroll = self.scroll.get();
+        if self.follow.get() {
+            if selected < scroll {
+                scroll = selected;
+            } else if selected >= scroll + height {
+                scroll = selected + 1 - height;
+            }
+            self.follow.set(false);
+        }
+        scroll = scroll.min(rows.len().saturating_sub(height));
+        if scroll != self.scroll.get() {
+            self.scroll.set(scroll);
+        }
+
+        let guide_style = Style::new().dim();
+        for line in 0..height {
+            let nth = scroll + line;
+            let goto = cursor::Goto(offset.x as u16, offset.y as u16 + line as u16);
+            let Some(row) = rows.get(nth) else {
+                write!(screen, "{}{}", goto, " ".repeat(width)).unwrap();
+                continue;
+            };
+            let guides = slice_columns(&row.guides, 0, width);
+            let rest = width.saturating_sub(str_width(&guides));
+            let label = slice_columns(&format!("{}{}", row.marker, row.label), 0, rest);
+            let pad = rest.saturating_sub(str_width(&label));
+            let style = if nth == selected {
+                if self.focused.get() {
+                    self.highlight
+                } else {
+                    Style::new().bold()
+                }
+            } else {
+                Style::new()
+            };
+            write!(
+                screen,
+                "{}{}{}{}{}{}{}",
+                goto,
+                guide_style,
+                guides,
+                style,
+                label,
+                " ".repeat(pad),
+                termion::style::Reset
+            )
+            .unwrap();
+        }
+    }
+}
+
+impl Hydrate for TreeView {
+    fn hydrate(&mut self, other: &Self) {
+        self.roots = other.roots.clone();
+        self.selected = other.selected.clone();
+        self.expanded = other.expanded.clone();
+        self.height = other.height;
+        self.guides = other.guides;
+        self.highlight = other.highlight;
+        self.on_activate = other.on_activate.clone();
+    }
+}
+
+impl View for TreeView {
+    fn children(&self) -> Vec<AnyView> {
+        Vec::new()
+    }
+
+    fn layout(&self, _children: &[ViewTree], constraint: Constraint) -> Size {
+        let rows = self.rows();
+        let widest = rows
+            .iter()
+            .map(|row| str_width(&row.guides) + str_width(row.marker) + str_width(&row.label))
+            .max();
+        Size {
+            width: widest.unwrap_or(0) as f64,
+            height: self
+                .height
+                .unwrap_or_else(|| (rows.len() as f64).min(constraint.max_height())),
+        }
+    }
+
+    fn focusable(&self) -> bool {
+        !self.roots.is_empty()
+    }
+
+    fn set_focus(&self, focused: bool) {
+        self.focused.set(focused);
+    }
+
+    fn event(&self, size: Size, offset: Offset, _children: &[ViewTree], event: &Event) -> bool {
+        let bounds = Rect::new(offset, size);
+        match *event {
+            Event::MousePress(MouseButton::Left, x, y) if bounds.contains(x, y) => {
+                self.click(offset, x, y);
+                true
+            }
+            Event::MousePress(MouseButton::WheelUp, x, y) if bounds.contains(x, y) => {
+                self.scroll.set(self.scroll.get().saturating_sub(3));
+                true
+            }
+            Event::MousePress(MouseButton::WheelDown, x, y) if bounds.contains(x, y) => {
+                self.scroll.set(self.scroll.get() + 3);
+                true
+            }
+            // Ctrl combinations are left to the containers, e.g. `Tabs`.
+            Event::KeyPress(key, mods) if !mods.ctrl => self.key_press(size, key),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    const SIZE: Size = Size {
+        width: 20.0,
+        height: 10.0,
+    };
+
+    fn path(ids: &[&str]) -> TreePath {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    fn labels(view: &TreeView) -> Vec<String> {
+        view.rows().into_iter().map(|row| row.label).collect()
+    }
+
+    fn files(selected: State<TreePath>, loads: Rc<Cell<usize>>) -> TreeView {
+        TreeView::new(selected).with_root(
+            TreeNode::new("root")
+                .with_child(TreeNode::new("src").with_child(TreeNode::new("main.rs")))
+                .with_child(TreeNode::new("target").lazy(move || {
+                    loads.set(loads.get() + 1);
+                    vec![TreeNode::new("debug"), TreeNode::new("release")]
+                })),
+        )
+    }
+
+    #[test]
+    fn expand_and_collapse() {
+        let view = files(State::new(path(&["root"])), Rc::default());
+        assert_eq!(labels(&view), ["root"]);
+        view.key_press(SIZE, KeyCode::Right);
+        assert_eq!(labels(&view), ["root", "src", "target"]);
+        view.key_press(SIZE, KeyCode::Down);
+        view.key_press(SIZE, KeyCode::Char(' '));
+        assert_eq!(labels(&view), ["root", "src", "main.rs", "target"]);
+        view.key_press(SIZE, KeyCode::Down);
+        assert_eq!(view.selected.get(), path(&["root", "src", "main.rs"]));
+
+        // Collapsing an ancestor moves the selection up to it.
+        view.set_expanded(&path(&["root"]), false);
+        assert_eq!(labels(&view), ["root"]);
+        assert_eq!(view.selected.get(), path(&["root"]));
+        // The nested node stays expanded.
+        view.key_press(SIZE, KeyCode::Right);
+        assert_eq!(labels(&view), ["root", "src", "main.rs", "target"]);
+    }
+
+    #[test]
+    fn lazy_children_load_once() {
+        let loads = Rc::new(Cell::new(0));
+        let view = files(State::new(path(&["root"])), loads.clone());
+        view.key_press(SIZE, KeyCode::Right);
+        assert_eq!(loads.get(), 0);
+
+        let target = path(&["root", "target"]);
+        view.set_expanded(&target, true);
+        assert_eq!(labels(&view), ["root", "src", "target", "debug", "release"]);
+        assert_eq!(loads.get(), 1);
+        view.set_expanded(&target, false);
+        view.set_expanded(&target, true);
+        assert_eq!(labels(&view).len(), 5);
+        assert_eq!(loads.get(), 1);
+    }
+
+    #[test]
+    fn selection_survives_rehydration() {
+        let selected = State::new(path(&["root"]));
+        let mut tree = ViewTree::new(files(selected.clone(), Rc::default()).any_view());
+        let view = |tree: &ViewTree| tree.view.downcast_ref::<TreeView>().unwrap().clone();
+        view(&tree).set_expanded(&path(&["root"]), true);
+        view(&tree).set_expanded(&path(&["root", "src"]), true);
+        selected.set(path(&["root", "src", "main.rs"]));
+
+        // A rebuilt tree keeps the expanded nodes and the selection, even
+        // when the selected node moved.
+        let reordered = TreeView::new(selected.clone()).with_root(
+            TreeNode::new("root")
+                .with_child(TreeNode::new("README"))
+                .with_child(TreeNode::new("src").with_child(TreeNode::new("main.rs"))),
+        );
+        tree.perform_hydrate(reordered.any_view());
+        let rows = view(&tree).rows();
+        assert_eq!(rows[view(&tree).selected_row(&rows)].label, "main.rs");
+
+        // A removed node falls back to its closest visible ancestor.
+        let pruned = TreeView::new(selected.clone())
+            .with_root(TreeNode::new("root").with_child(TreeNode::new("src")));
+        tree.perform_hydrate(pruned.any_view());
+        let rows = view(&tree).rows();
+        assert_eq!(rows[view(&tree).selected_row(&rows)].label, "src");
+        assert_eq!(selected.get(), path(&["root", "src", "main.rs"]));
+    }
+}
@@ -0,0 +1,173 @@
+use quill::*;
+use quill_derive::*;
+use quill_termion::*;
+use std::io::Write;
+
+#[derive(Debug, Clone, PartialEq, Hydrate)]
+struct App {
+    clicks: State<u32>,
+    frames: State<u32>,
+}
+
+impl View for App {
+    fn body(&self) -> AnyView {
+        let mut stack = Stack::new();
+        stack.push(
+            TermText::new(format!("Clicks: {}", self.clicks.get())).on_click({
+                let clicks = self.clicks.clone();
+                move || clicks.set(clicks.get() + 1)
+            }),
+        );
+        for _ in 0..self.clicks.get() {
+            stack.push(TermText::new("click"));
+        }
+        stack.any_view()
+    }
+
+    fn event(&self, _size: Size, _offset: Offset, _children: &[ViewTree], event: &Event) -> bool {
+        if let Event::Frame(_) = event {
+            self.frames.set(self.frames.get() + 1);
+        }
+        false
+    }
+}
+
+fn app() -> App {
+    App {
+        clicks: State::new(0),
+        frames: State::new(0),
+    }
+}
+
+/// Times of the idle frames `first..last`, at 60 frames a second.
+fn idle(first: u64, last: u64) -> Vec<f64> {
+    (first..last).map(|frame| frame as f64 / 60.0).collect()
+}
+
+fn write_script(name: &str, script: &[FrameRecord]) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!("quill-{}-{}.jsonl", name, std::process::id()));
+    let mut file = std::fs::File::create(&path).unwrap();
+    for record in script {
+        writeln!(file, "{}", serde_json::to_string(record).unwrap()).unwrap();
+    }
+    path
+}
+
+fn headless(stop_at: Option<u64>) -> ReplayOptions {
+    ReplayOptions {
+        headless: true,
+        stop_at,
+    }
+}
+
+fn script() -> Vec<FrameRecord> {
+    let click = Event::MousePress(MouseButton::Left, 1.0, 1.0);
+    vec![
+        FrameRecord {
+            frame: 0,
+            time: 0.0,
+            size: Some(Size::new(40.0, 10.0)),
+            events: Vec::new(),
+            idle: Vec::new(),
+        },
+        FrameRecord {
+            frame: 12,
+            time: 0.2,
+            size: None,
+            events: vec![click, click],
+            idle: idle(1, 12),
+        },
+        FrameRecord {
+            frame: 30,
+            time: 0.5,
+            size: Some(Size::new(20.0, 5.0)),
+            events: vec![click],
+            idle: idle(13, 30),
+        },
+    ]
+}
+
+#[test]
+fn headless_replay_is_deterministic() {
+    let path = write_script("replay", &script());
+    assert_eq!(read_recording(&path).unwrap(), script());
+
+    let first = replay(app(), &path, headless(None)).unwrap();
+    let second = replay(app(), &path, headless(None)).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(first.snapshot(), second.snapshot());
+    let app = first.view.downcast_ref::<App>().unwrap();
+    assert_eq!(app.clicks.get(), 3);
+    // Idle frames are played too.
+    assert_eq!(app.frames.get(), 31);
+    assert_eq!(first.snapshot().children[0].children.len(), 4);
+}
+
+#[test]
+fn replay_stops_at_an_idle_frame() {
+    let path = write_script("stop-at", &script());
+    let tree = replay(app(), &path, headless(Some(20))).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let app = tree.view.downcast_ref::<App>().unwrap();
+    assert_eq!(app.clicks.get(), 2);
+    assert_eq!(app.frames.get(), 21);
+}
+
+#[derive(Debug, Clone, PartialEq, Hydrate)]
+struct Pick {
+    selected: State<usize>,
+    activated: State<u32>,
+}
+
+impl View for Pick {
+    fn body(&self) -> AnyView {
+        let activated = self.activated.clone();
+        List::new(self.selected.clone())
+            .with_items(["a", "b"])
+            .on_activate(move |_| activated.set(activated.get() + 1))
+            .any_view()
+    }
+}
+
+#[test]
+fn double_clicks_follow_the_recorded_frame_times() {
+    let click = Event::MousePress(MouseButton::Left, 1.0, 1.0);
+    let start = FrameRecord {
+        frame: 0,
+        time: 0.0,
+        size: Some(Size::new(20.0, 5.0)),
+        events: Vec::new(),
+        idle: Vec::new(),
+    };
+    let click = |frame: u64, idle| FrameRecord {
+        frame,
+        time: frame as f64 / 60.0,
+        size: None,
+        events: vec![click],
+        idle,
+    };
+    let activations = |script: &[FrameRecord]| {
+        let path = write_script("clicks", script);
+        let pick = Pick {
+            selected: State::new(0),
+            activated: State::new(0),
+        };
+        let tree = replay(pick, &path, headless(None)).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let activated = tree.view.downcast_ref::<Pick>().unwrap().activated.get();
+        activated
+    };
+
+    // A second apart: two single clicks, even when replayed back to back.
+    let script = [
+        start.clone(),
+        click(30, idle(1, 30)),
+        click(90, idle(31, 90)),
+    ];
+    assert_eq!(activations(&script), 0);
+    // Two frames apart: a double-click.
+    let script = [start, click(30, idle(1, 30)), click(32, idle(31, 32))];
+    assert_eq!(activations(&script), 1);
+}
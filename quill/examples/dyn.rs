@@ -89,12 +89,7 @@ fn main() {
 
 fn _layout_test() {
     let tree = ViewTree::new(App.any_view());
-    tree.layout(Constraint {
-        min_width: 0.0,
-        max_width: 100.0,
-        min_height: 0.0,
-        max_height: 100.0,
-    });
+    tree.layout(Constraint::new(100.0, 100.0));
     tree.set_offset(Offset::zero());
     tree.pretty_print();
 }
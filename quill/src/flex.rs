@@ -0,0 +1,555 @@
+use crate::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    Horizontal,
+    Vertical,
+}
+
+impl Axis {
+    fn main(self, size: Size) -> f64 {
+        match self {
+            Axis::Horizontal => size.width,
+            Axis::Vertical => size.height,
+        }
+    }
+
+    fn cross(self, size: Size) -> f64 {
+        match self {
+            Axis::Horizontal => size.height,
+            Axis::Vertical => size.width,
+        }
+    }
+
+    fn size(self, main: f64, cross: f64) -> Size {
+        match self {
+            Axis::Horizontal => Size::new(main, cross),
+            Axis::Vertical => Size::new(cross, main),
+        }
+    }
+
+    fn offset(self, main: f64, cross: f64) -> Offset {
+        match self {
+            Axis::Horizontal => Offset::new(main, cross),
+            Axis::Vertical => Offset::new(cross, main),
+        }
+    }
+
+    fn is_main(self, kind: Intrinsic) -> bool {
+        kind.is_width() == (self == Axis::Horizontal)
+    }
+
+    /// A constraint between `min` and `max` on the main axis and up to
+    /// `cross` on the other.
+    fn constraint(self, min: f64, max: f64, cross: (f64, f64)) -> Constraint {
+        Constraint::between(self.size(min, cross.0), self.size(max, cross.1))
+    }
+}
+
+/// Where the free space on the main axis goes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Justify {
+    #[default]
+    Start,
+    End,
+    Center,
+    /// Between the children, none at the ends.
+    SpaceBetween,
+    /// Around each child, so the ends get half as much as the gaps.
+    SpaceAround,
+    /// Equally between the children and at the ends.
+    SpaceEvenly,
+}
+
+impl Justify {
+    /// Space before the first child and between two children.
+    fn spacing(self, free: f64, count: usize) -> (f64, f64) {
+        let count = count as f64;
+        match self {
+            Justify::Start => (0.0, 0.0),
+            Justify::End => (free, 0.0),
+            Justify::Center => ((free / 2.0).floor(), 0.0),
+            Justify::SpaceBetween if count > 1.0 => (0.0, free / (count - 1.0)),
+            Justify::SpaceBetween => (0.0, 0.0),
+            Justify::SpaceAround => {
+                let around = free / count;
+                (around / 2.0, around)
+            }
+            Justify::SpaceEvenly => {
+                let between = free / (count + 1.0);
+                (between, between)
+            }
+        }
+    }
+}
+
+/// Where children go on the cross axis of their line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AlignItems {
+    #[default]
+    Start,
+    End,
+    Center,
+    /// Lay children out again at the full cross size of the line.
+    Stretch,
+}
+
+/// How a child of a `Flex` takes part in distributing space. Other views
+/// behave as `FlexItem`s with the default factors.
+#[derive(Debug, Clone, PartialEq, Hydrate)]
+pub struct FlexItem {
+    pub child: AnyView,
+    /// Share of the free space the child grows by. 0 keeps its natural size.
+    pub grow: f64,
+    /// Share of the missing space the child gives up, weighted by its natural
+    /// size. 0 keeps its natural size.
+    pub shrink: f64,
+    /// Children with a lower priority shrink first, and only give space to
+    /// higher ones once they reached zero.
+    pub priority: f64,
+}
+
+pub trait Flexible: View + Sized {
+    fn flex(self, grow: f64) -> FlexItem {
+        FlexItem::new(self).flex(grow)
+    }
+
+    fn flex_shrink(self, shrink: f64) -> FlexItem {
+        FlexItem::new(self).flex_shrink(shrink)
+    }
+
+    fn layout_priority(self, priority: f64) -> FlexItem {
+        FlexItem::new(self).layout_priority(priority)
+    }
+}
+impl<X: View> Flexible for X {}
+
+impl FlexItem {
+    pub fn new(child: impl View) -> Self {
+        FlexItem {
+            child: child.any_view(),
+            grow: 0.0,
+            shrink: 1.0,
+            priority: 0.0,
+        }
+    }
+
+    pub fn flex(mut self, grow: f64) -> Self {
+        self.grow = grow.max(0.0);
+        self
+    }
+
+    pub fn flex_shrink(mut self, shrink: f64) -> Self {
+        self.shrink = shrink.max(0.0);
+        self
+    }
+
+    pub fn layout_priority(mut self, priority: f64) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    fn of(view: &AnyView) -> (f64, f64, f64) {
+        view.downcast_ref::<FlexItem>()
+            .map_or((0.0, 1.0, 0.0), |item| {
+                (item.grow, item.shrink, item.priority)
+            })
+    }
+}
+
+impl View for FlexItem {
+    fn body(&self) -> AnyView {
+        self.child.clone()
+    }
+}
+
+/// Lays out children along an axis, sharing the space between them with the
+/// factors of `FlexItem`, and optionally wrapping them onto several lines.
+#[derive(Debug)]
+pub struct Flex {
+    pub axis: Axis,
+    pub justify: Justify,
+    pub align_items: AlignItems,
+    pub wrap: bool,
+    /// Cells between children, and between lines when wrapping.
+    pub gap: f64,
+    pub children: Vec<AnyView>,
+    // Child positions relative to the view, from the last layout.
+    positions: State<Vec<Offset>>,
+}
+
+impl Clone for Flex {
+    fn clone(&self) -> Self {
+        Flex {
+            children: self.children.clone(),
+            positions: self.positions.clone(),
+            ..*self
+        }
+    }
+}
+
+impl Flex {
+    pub fn new(axis: Axis) -> Self {
+        Flex {
+            axis,
+            justify: Justify::Start,
+            align_items: AlignItems::Start,
+            wrap: false,
+            gap: 0.0,
+            children: Vec::new(),
+            positions: State::new(Vec::new()),
+        }
+    }
+
+    pub fn row() -> Self {
+        Flex::new(Axis::Horizontal)
+    }
+
+    pub fn column() -> Self {
+        Flex::new(Axis::Vertical)
+    }
+
+    pub fn justify(mut self, justify: Justify) -> Self {
+        self.justify = justify;
+        self
+    }
+
+    pub fn align_items(mut self, align_items: AlignItems) -> Self {
+        self.align_items = align_items;
+        self
+    }
+
+    /// Start a new line when the next child does not fit.
+    pub fn wrap(mut self) -> Self {
+        self.wrap = true;
+        self
+    }
+
+    pub fn gap(mut self, gap: f64) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    #[track_caller]
+    pub fn with_child(mut self, child: impl View) -> Self {
+        self.children.push(child.any_view());
+        self
+    }
+
+    #[track_caller]
+    pub fn push(&mut self, child: impl View) {
+        self.children.push(child.any_view());
+    }
+
+    /// Main axis sizes of one line from the natural sizes of its children.
+    fn distribute(&self, line: &[ViewTree], natural: &[f64], available: f64) -> Vec<f64> {
+        if !available.is_finite() {
+            return natural.to_vec();
+        }
+        let gaps = self.gap * line.len().saturating_sub(1) as f64;
+        let free = available - gaps - natural.iter().sum::<f64>();
+        let factors: Vec<_> = line.iter().map(|child| FlexItem::of(&child.view)).collect();
+        share(natural, &factors, free)
+    }
+
+    /// Split children into lines of at most `available` on the main axis.
+    fn lines(&self, natural: &[f64], available: f64) -> Vec<std::ops::Range<usize>> {
+        let available = if self.wrap { available } else { f64::INFINITY };
+        let mut lines = Vec::new();
+        let mut start = 0;
+        let mut used = 0.0;
+        for (nth, size) in natural.iter().enumerate() {
+            if nth > start && used + self.gap + size > available {
+                lines.push(start..nth);
+                start = nth;
+                used = 0.0;
+            }
+            used += if nth > start { self.gap + size } else { *size };
+        }
+        lines.push(start..natural.len());
+        lines
+    }
+}
+
+/// Sizes of children with `natural` sizes and `(grow, shrink, priority)`
+/// factors once `free` cells are shared out, or taken back when negative.
+/// Whole cells are shared like the fractions of a `Grid`.
+fn share(natural: &[f64], factors: &[(f64, f64, f64)], free: f64) -> Vec<f64> {
+    let mut sizes = natural.to_vec();
+    if free > 0.0 {
+        let weights: Vec<f64> = factors.iter().map(|(grow, _, _)| *grow).collect();
+        for (size, share) in sizes.iter_mut().zip(grid::distribute(free, &weights)) {
+            *size += share;
+        }
+    } else if free < 0.0 {
+        // Take the missing space from the lowest priority first. Children
+        // that reach zero drop out and the rest give up what they could not.
+        let mut missing = (-free).ceil();
+        let mut priorities: Vec<f64> = factors.iter().map(|(_, _, priority)| *priority).collect();
+        priorities.sort_by(f64::total_cmp);
+        priorities.dedup();
+        for priority in priorities {
+            while missing > 0.0 {
+                let weights: Vec<f64> = factors
+                    .iter()
+                    .zip(&sizes)
+                    .map(|((_, shrink, level), size)| {
+                        if *level == priority {
+                            shrink * size.max(0.0)
+                        } else {
+                            0.0
+                        }
+                    })
+                    .collect();
+                if weights.iter().all(|weight| *weight <= 0.0) {
+                    break;
+                }
+                let mut taken = 0.0;
+                for (size, take) in sizes.iter_mut().zip(grid::distribute(missing, &weights)) {
+                    let take = take.min(*size);
+                    *size -= take;
+                    taken += take;
+                }
+                if taken == 0.0 {
+                    // Less than a cell is missing, e.g. after a fractional
+                    // child: the first child that can still shrink gives it.
+                    if let Some((size, _)) = sizes
+                        .iter_mut()
+                        .zip(&weights)
+                        .find(|(_, weight)| **weight > 0.0)
+                    {
+                        taken = missing.min(*size);
+                        *size -= taken;
+                    }
+                }
+                missing -= taken;
+            }
+        }
+    }
+    sizes
+}
+
+impl Hydrate for Flex {
+    fn hydrate(&mut self, other: &Self) {
+        self.axis = other.axis;
+        self.justify = other.justify;
+        self.align_items = other.align_items;
+        self.wrap = other.wrap;
+        self.gap = other.gap;
+        self.children = other.children.clone();
+    }
+}
+
+impl View for Flex {
+    fn children(&self) -> Vec<AnyView> {
+        self.children.clone()
+    }
+
+    fn layout(&self, children: &[ViewTree], constraint: Constraint) -> Size {
+        let axis = self.axis;
+        let available = axis.main(constraint.max_size());
+        let max_cross = axis.cross(constraint.max_size());
+        // Natural sizes, measured without a limit on the main axis.
+        let natural_kind = match axis {
+            Axis::Horizontal => Intrinsic::MaxWidth,
+            Axis::Vertical => Intrinsic::MaxHeight,
+        };
+        let natural: Vec<f64> = children
+            .iter()
+            .map(|child| bounded(child.intrinsic(natural_kind, max_cross), available))
+            .collect();
+        let fills = self.justify != Justify::Start
+            || children
+                .iter()
+                .any(|child| FlexItem::of(&child.view).0 > 0.0);
+        let mut positions = vec![Offset::zero(); children.len()];
+        let mut main: f64 = 0.0;
+        let mut cross = 0.0;
+        for (nth, range) in self.lines(&natural, available).into_iter().enumerate() {
+            let line = &children[range.clone()];
+            let sizes = self.distribute(line, &natural[range.clone()], available);
+            let remaining = (max_cross - cross).max(0.0);
+            let mut line_cross: f64 = 0.0;
+            for (child, size) in line.iter().zip(&sizes) {
+                child.layout(axis.constraint(*size, *size, (0.0, remaining)));
+                line_cross = line_cross.max(axis.cross(child.view.size.get()));
+            }
+            if self.align_items == AlignItems::Stretch {
+                for (child, size) in line.iter().zip(&sizes) {
+                    child.layout(axis.constraint(*size, *size, (line_cross, line_cross)));
+                }
+            }
+            let used = sizes.iter().sum::<f64>() + self.gap * line.len().saturating_sub(1) as f64;
+            let free = if available.is_finite() {
+                (available - used).max(0.0)
+            } else {
+                0.0
+            };
+            let (start, between) = self.justify.spacing(free, line.len());
+            if nth > 0 {
+                cross += self.gap;
+            }
+            let mut at = start;
+            // Advance by the share of each child, so a child that overflows
+            // it does not push the others.
+            for ((nth, child), share) in range.zip(line).zip(&sizes) {
+                let size = child.view.size.get();
+                let free_cross = line_cross - axis.cross(size);
+                let shift = match self.align_items {
+                    AlignItems::Start | AlignItems::Stretch => 0.0,
+                    AlignItems::End => free_cross,
+                    AlignItems::Center => (free_cross / 2.0).floor(),
+                };
+                positions[nth] = axis.offset(at.floor(), cross + shift);
+                at += share + self.gap + between;
+            }
+            main = main.max(if fills && available.is_finite() {
+                available
+            } else {
+                used
+            });
+            cross += line_cross;
+        }
+        if positions != self.positions.get() {
+            self.positions.set(positions);
+        }
+        constraint.constrain(axis.size(main, cross))
+    }
+
+    fn intrinsic(&self, children: &[ViewTree], kind: Intrinsic, extent: f64) -> f64 {
+        let sizes = children.iter().map(|child| child.intrinsic(kind, extent));
+        if self.axis.is_main(kind) {
+            if self.wrap && kind.is_min() {
+                // Every child on a line of its own.
+                sizes.fold(0.0, f64::max)
+            } else {
+                sizes.sum::<f64>() + self.gap * children.len().saturating_sub(1) as f64
+            }
+        } else if !self.wrap {
+            children
+                .iter()
+                .map(|child| child.intrinsic(kind, f64::INFINITY))
+                .fold(0.0, f64::max)
+        } else {
+            // Break the lines at `extent` like the layout does, then stack
+            // the tallest child of each.
+            let main_kind = if kind.is_width() {
+                Intrinsic::MaxHeight
+            } else {
+                Intrinsic::MaxWidth
+            };
+            let natural: Vec<f64> = children
+                .iter()
+                .map(|child| bounded(child.intrinsic(main_kind, f64::INFINITY), extent))
+                .collect();
+            let lines = self.lines(&natural, extent);
+            let gaps = self.gap * lines.len().saturating_sub(1) as f64;
+            let lines = lines.into_iter().map(|range| {
+                let line = &children[range.clone()];
+                let sizes = self.distribute(line, &natural[range], extent);
+                line.iter()
+                    .zip(sizes)
+                    .map(|(child, size)| child.intrinsic(kind, size))
+                    .fold(0.0, f64::max)
+            });
+            lines.sum::<f64>() + gaps
+        }
+    }
+
+    fn set_offset(&self, children: &[ViewTree], offset: Offset) {
+        for (child, at) in children.iter().zip(self.positions.get()) {
+            child.set_offset(offset.translate(at.x, at.y));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grow_shares_whole_cells() {
+        let factors = [(1.0, 1.0, 0.0), (1.0, 1.0, 0.0), (1.0, 1.0, 0.0)];
+        assert_eq!(share(&[2.0, 2.0, 2.0], &factors, 10.0), [6.0, 5.0, 5.0]);
+        let factors = [(0.0, 1.0, 0.0), (2.0, 1.0, 0.0)];
+        assert_eq!(share(&[3.0, 1.0], &factors, 4.5), [3.0, 5.0]);
+    }
+
+    #[test]
+    fn shrink_takes_no_more_than_missing() {
+        let factors = [(0.0, 1.0, 0.0); 3];
+        let sizes = share(&[10.0, 10.0, 10.0], &factors, -10.0);
+        assert_eq!(sizes.iter().sum::<f64>(), 20.0);
+        assert_eq!(sizes, [6.0, 7.0, 7.0]);
+    }
+
+    #[test]
+    fn shrink_passes_on_what_zero_sized_children_could_not_give() {
+        // The small child is weighted to lose 5 but only has 2.
+        let factors = [(0.0, 10.0, 0.0), (0.0, 1.0, 0.0)];
+        let sizes = share(&[2.0, 20.0], &factors, -10.0);
+        assert_eq!(sizes, [0.0, 12.0]);
+    }
+
+    #[test]
+    fn shrink_by_priority_then_stop_at_zero() {
+        let factors = [(0.0, 1.0, 1.0), (0.0, 1.0, 0.0), (0.0, 0.0, 0.0)];
+        assert_eq!(share(&[10.0, 4.0, 5.0], &factors, -6.0), [8.0, 0.0, 5.0]);
+        // More missing than every child has: all shrinkable ones reach zero.
+        assert_eq!(share(&[10.0, 4.0, 5.0], &factors, -40.0), [0.0, 0.0, 5.0]);
+    }
+
+    #[test]
+    fn shrink_takes_fractions_left_by_fractional_children() {
+        let factors = [(0.0, 1.0, 0.0); 2];
+        assert_eq!(share(&[0.5, 10.0], &factors, -0.5), [0.0, 9.5]);
+    }
+
+    #[derive(Debug, Clone, PartialEq, Hydrate)]
+    struct Fixed(f64);
+
+    impl View for Fixed {
+        fn children(&self) -> Vec<AnyView> {
+            Vec::new()
+        }
+
+        fn layout(&self, _children: &[ViewTree], constraint: Constraint) -> Size {
+            constraint.constrain(Size::new(self.0, 1.0))
+        }
+    }
+
+    #[test]
+    fn shrinking_fractional_children_terminates() {
+        let flex = Flex::row()
+            .with_child(Fixed(0.5))
+            .with_child(Text::new("aaaaaaaaaa"));
+        let tree = ViewTree::new(flex.any_view());
+        tree.layout(Constraint::new(10.0, 5.0));
+        let widths: Vec<f64> = tree
+            .children
+            .iter()
+            .map(|child| child.view.constraint.get().max_width())
+            .collect();
+        assert_eq!(widths, [0.0, 9.5]);
+    }
+
+    #[test]
+    fn measuring_wrapped_lines_does_not_lay_out() {
+        let flex = Flex::row()
+            .wrap()
+            .gap(1.0)
+            .with_child(Text::new("aaaa"))
+            .with_child(Text::new("bbbb"))
+            .with_child(Text::new("cc"));
+        let tree = ViewTree::new(flex.any_view());
+        // Lines of one row with a row of gap between them.
+        assert_eq!(tree.max_intrinsic_height(12.0), 1.0);
+        assert_eq!(tree.max_intrinsic_height(10.0), 3.0);
+        assert_eq!(tree.max_intrinsic_height(6.0), 5.0);
+        for child in &tree.children {
+            assert_eq!(child.view.size.get(), Size::zero());
+        }
+        tree.layout(Constraint::new(10.0, 10.0));
+        assert_eq!(tree.view.size.get().height, 3.0);
+    }
+}
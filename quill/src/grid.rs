@@ -0,0 +1,428 @@
+use crate::*;
+
+/// The size of a row or column of a `Grid`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Track {
+    /// Exactly this many cells.
+    Fixed(f64),
+    /// A share of the space left by the other tracks, in proportion to the
+    /// other fractions. Behaves like `Auto` when the space is unbounded.
+    Fraction(f64),
+    /// As large as the largest child in the track.
+    Auto,
+}
+
+/// A child of a `Grid` with its position, span and alignment in the grid.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GridCell {
+    pub child: AnyView,
+    pub row: usize,
+    pub column: usize,
+    // At least 1, set with `span`.
+    row_span: usize,
+    column_span: usize,
+    /// Overrides the alignment of the grid for this cell.
+    pub alignment: Option<Alignment>,
+}
+
+impl GridCell {
+    #[track_caller]
+    pub fn new(child: impl View) -> Self {
+        GridCell {
+            child: child.any_view(),
+            row: 0,
+            column: 0,
+            row_span: 1,
+            column_span: 1,
+            alignment: None,
+        }
+    }
+
+    pub fn at(mut self, row: usize, column: usize) -> Self {
+        self.row = row;
+        self.column = column;
+        self
+    }
+
+    pub fn span(mut self, rows: usize, columns: usize) -> Self {
+        self.row_span = rows.max(1);
+        self.column_span = columns.max(1);
+        self
+    }
+
+    pub fn align(mut self, alignment: Alignment) -> Self {
+        self.alignment = Some(alignment);
+        self
+    }
+}
+
+// Start and size of every track on one axis, from the last layout.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Tracks {
+    starts: Vec<f64>,
+    sizes: Vec<f64>,
+}
+
+impl Tracks {
+    fn new(sizes: Vec<f64>, gap: f64) -> Self {
+        let mut starts = Vec::with_capacity(sizes.len());
+        let mut at = 0.0;
+        for size in &sizes {
+            starts.push(at);
+            at += size + gap;
+        }
+        Tracks { starts, sizes }
+    }
+
+    fn total(&self, gap: f64) -> f64 {
+        let sizes: f64 = self.sizes.iter().sum();
+        sizes + gap * self.sizes.len().saturating_sub(1) as f64
+    }
+
+    /// Start and size of `span` tracks from `first`, gaps included.
+    fn area(&self, first: usize, span: usize, gap: f64) -> (f64, f64) {
+        let last = (first + span).min(self.sizes.len());
+        let size: f64 = self.sizes[first..last].iter().sum();
+        let gaps = (last - first).saturating_sub(1);
+        (self.starts[first], size + gap * gaps as f64)
+    }
+}
+
+/// Lays out children in rows and columns.
+///
+/// Cells are placed with `GridCell::at` and can span several tracks. Rows and
+/// columns that cells use beyond the declared tracks are added as `Auto`.
+/// Each cell gets the area of its tracks and is aligned inside it.
+#[derive(Debug, Clone)]
+pub struct Grid {
+    pub columns: Vec<Track>,
+    pub rows: Vec<Track>,
+    pub column_gap: f64,
+    pub row_gap: f64,
+    pub cells: Vec<GridCell>,
+    pub alignment: Alignment,
+    // Track positions from the last layout, used to place the cells.
+    tracks: State<(Tracks, Tracks)>,
+}
+
+impl Default for Grid {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Grid {
+    pub fn new() -> Self {
+        Grid {
+            columns: Vec::new(),
+            rows: Vec::new(),
+            column_gap: 0.0,
+            row_gap: 0.0,
+            cells: Vec::new(),
+            alignment: Alignment::TOP_LEADING,
+            tracks: State::new(Default::default()),
+        }
+    }
+
+    pub fn columns(mut self, columns: impl IntoIterator<Item = Track>) -> Self {
+        self.columns = columns.into_iter().collect();
+        self
+    }
+
+    pub fn rows(mut self, rows: impl IntoIterator<Item = Track>) -> Self {
+        self.rows = rows.into_iter().collect();
+        self
+    }
+
+    /// Empty cells between rows and between columns.
+    pub fn gap(mut self, row_gap: f64, column_gap: f64) -> Self {
+        self.row_gap = row_gap;
+        self.column_gap = column_gap;
+        self
+    }
+
+    /// The default alignment of the cells.
+    pub fn align(mut self, alignment: Alignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    pub fn with_cell(mut self, cell: GridCell) -> Self {
+        self.cells.push(cell);
+        self
+    }
+
+    pub fn push(&mut self, cell: GridCell) {
+        self.cells.push(cell);
+    }
+
+    /// Put `child` in a single cell.
+    #[track_caller]
+    pub fn with_child(self, row: usize, column: usize, child: impl View) -> Self {
+        self.with_cell(GridCell::new(child).at(row, column))
+    }
+
+    /// Columns and rows sharing `available`, from the `width` and `height`
+    /// intrinsic sizes of the children.
+    fn measure_tracks(
+        &self,
+        children: &[ViewTree],
+        available: Size,
+        width: Intrinsic,
+        height: Intrinsic,
+    ) -> (Tracks, Tracks) {
+        let cells = &self.cells[..children.len().min(self.cells.len())];
+        let columns = Grid::all_tracks(
+            &self.columns,
+            cells.iter().map(|cell| cell.column + cell.column_span),
+        );
+        let rows = Grid::all_tracks(
+            &self.rows,
+            cells.iter().map(|cell| cell.row + cell.row_span),
+        );
+
+        // Columns from the natural widths of the children.
+        let spans: Vec<_> = cells
+            .iter()
+            .zip(children)
+            .map(|(cell, child)| {
+                let size = bounded(child.intrinsic(width, available.height), available.width);
+                (cell.column, cell.column_span, size)
+            })
+            .collect();
+        let widths = Tracks::new(
+            size_tracks(&columns, &spans, available.width, self.column_gap),
+            self.column_gap,
+        );
+
+        // Rows from the heights of the children at their column width.
+        let spans: Vec<_> = cells
+            .iter()
+            .zip(children)
+            .map(|(cell, child)| {
+                let (_, column) = widths.area(cell.column, cell.column_span, self.column_gap);
+                let size = bounded(child.intrinsic(height, column), available.height);
+                (cell.row, cell.row_span, size)
+            })
+            .collect();
+        let heights = Tracks::new(
+            size_tracks(&rows, &spans, available.height, self.row_gap),
+            self.row_gap,
+        );
+        (widths, heights)
+    }
+
+    /// Declared tracks, followed by `Auto` tracks up to the last one used.
+    fn all_tracks(declared: &[Track], used: impl Iterator<Item = usize>) -> Vec<Track> {
+        let mut tracks = declared.to_vec();
+        let count = used.max().unwrap_or(0).max(tracks.len());
+        tracks.resize(count, Track::Auto);
+        tracks
+    }
+}
+
+impl Hydrate for Grid {
+    fn hydrate(&mut self, other: &Self) {
+        self.columns = other.columns.clone();
+        self.rows = other.rows.clone();
+        self.column_gap = other.column_gap;
+        self.row_gap = other.row_gap;
+        self.cells = other.cells.clone();
+        self.alignment = other.alignment;
+    }
+}
+
+/// Sizes of `tracks` sharing `available` space. `spans` gives the first
+/// track, the number of tracks and the content size of every cell.
+fn size_tracks(
+    tracks: &[Track],
+    spans: &[(usize, usize, f64)],
+    available: f64,
+    gap: f64,
+) -> Vec<f64> {
+    let fill = available.is_finite();
+    let is_auto = |track: &Track| match track {
+        Track::Auto => true,
+        Track::Fraction(_) => !fill,
+        Track::Fixed(_) => false,
+    };
+    let mut sizes: Vec<f64> = tracks
+        .iter()
+        .map(|track| match track {
+            Track::Fixed(size) => size.max(0.0),
+            _ => 0.0,
+        })
+        .collect();
+    // Single-track cells first, then spanning cells grow the last automatic
+    // track they cover if they do not fit yet.
+    for (first, _, size) in spans.iter().filter(|(_, span, _)| *span == 1) {
+        if is_auto(&tracks[*first]) {
+            sizes[*first] = sizes[*first].max(*size);
+        }
+    }
+    for (first, span, size) in spans.iter().filter(|(_, span, _)| *span > 1) {
+        let last = (first + span).min(tracks.len());
+        let covered: f64 =
+            sizes[*first..last].iter().sum::<f64>() + gap * (last - first - 1) as f64;
+        if let Some(auto) = (*first..last).rev().find(|&nth| is_auto(&tracks[nth])) {
+            sizes[auto] += (size - covered).max(0.0);
+        }
+    }
+    if fill {
+        let weights: Vec<f64> = tracks
+            .iter()
+            .map(|track| match track {
+                Track::Fraction(weight) => weight.max(0.0),
+                _ => 0.0,
+            })
+            .collect();
+        let used: f64 = sizes.iter().sum::<f64>() + gap * tracks.len().saturating_sub(1) as f64;
+        let shares = distribute((available - used).max(0.0), &weights);
+        for (size, share) in sizes.iter_mut().zip(shares) {
+            *size += share;
+        }
+    }
+    sizes
+}
+
+/// Whole cells of `total` split in proportion to `weights`. Cells lost to
+/// rounding go to the first tracks.
+pub(crate) fn distribute(total: f64, weights: &[f64]) -> Vec<f64> {
+    let sum: f64 = weights.iter().sum();
+    if sum <= 0.0 {
+        return vec![0.0; weights.len()];
+    }
+    let mut shares: Vec<f64> = weights
+        .iter()
+        .map(|weight| (total * weight / sum).floor())
+        .collect();
+    let mut left = (total.floor() - shares.iter().sum::<f64>()) as usize;
+    for (share, weight) in shares.iter_mut().zip(weights) {
+        if left == 0 {
+            break;
+        }
+        if *weight > 0.0 {
+            *share += 1.0;
+            left -= 1;
+        }
+    }
+    shares
+}
+
+impl View for Grid {
+    fn children(&self) -> Vec<AnyView> {
+        self.cells.iter().map(|cell| cell.child.clone()).collect()
+    }
+
+    fn layout(&self, children: &[ViewTree], constraint: Constraint) -> Size {
+        let (widths, heights) = self.measure_tracks(
+            children,
+            constraint.max_size(),
+            Intrinsic::MaxWidth,
+            Intrinsic::MaxHeight,
+        );
+        let cells = &self.cells[..children.len().min(self.cells.len())];
+        for (cell, child) in cells.iter().zip(children) {
+            let (_, width) = widths.area(cell.column, cell.column_span, self.column_gap);
+            let (_, height) = heights.area(cell.row, cell.row_span, self.row_gap);
+            child.layout(Constraint::new(width, height));
+        }
+        let size = constraint.constrain(Size::new(
+            widths.total(self.column_gap),
+            heights.total(self.row_gap),
+        ));
+        let tracks = (widths, heights);
+        if tracks != self.tracks.get() {
+            self.tracks.set(tracks);
+        }
+        size
+    }
+
+    fn intrinsic(&self, children: &[ViewTree], kind: Intrinsic, extent: f64) -> f64 {
+        if kind.is_width() {
+            let available = Size::new(f64::INFINITY, extent);
+            let (widths, _) = self.measure_tracks(children, available, kind, Intrinsic::MaxHeight);
+            widths.total(self.column_gap)
+        } else {
+            let available = Size::new(extent, f64::INFINITY);
+            let (_, heights) = self.measure_tracks(children, available, Intrinsic::MaxWidth, kind);
+            heights.total(self.row_gap)
+        }
+    }
+
+    fn set_offset(&self, children: &[ViewTree], offset: Offset) {
+        let (widths, heights) = self.tracks.get();
+        for (cell, child) in self.cells.iter().zip(children) {
+            let (x, width) = widths.area(cell.column, cell.column_span, self.column_gap);
+            let (y, height) = heights.area(cell.row, cell.row_span, self.row_gap);
+            let alignment = cell.alignment.unwrap_or(self.alignment);
+            let at = alignment.position(child.view.size.get(), Size::new(width, height));
+            child.set_offset(offset.translate(x + at.x, y + at.y));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Takes all the width it is given, like a progress bar.
+    #[derive(Debug, Clone, PartialEq, Hydrate)]
+    struct Fill;
+
+    impl View for Fill {
+        fn children(&self) -> Vec<AnyView> {
+            Vec::new()
+        }
+
+        fn layout(&self, _children: &[ViewTree], constraint: Constraint) -> Size {
+            Size::new(constraint.max_width(), 1.0)
+        }
+    }
+
+    fn place(grid: Grid) -> ViewTree {
+        let tree = ViewTree::new(grid.any_view());
+        tree.layout(Constraint::new(80.0, 24.0));
+        tree.set_offset(Offset::zero());
+        tree
+    }
+
+    #[test]
+    fn fill_width_child_in_auto_track_stays_finite() {
+        let tree = place(
+            Grid::new()
+                .with_child(0, 0, Fill)
+                .with_child(0, 1, Text::new("label")),
+        );
+        for child in &tree.children {
+            let rect = child.view.rect();
+            assert!(rect.size.width.is_finite() && rect.left().is_finite());
+        }
+        assert_eq!(tree.children[0].view.size.get().width, 80.0);
+        assert_eq!(tree.view.size.get(), Size::new(80.0, 1.0));
+    }
+
+    #[test]
+    fn empty_span_covers_one_track() {
+        let tree = place(
+            Grid::new()
+                .columns([Track::Fixed(3.0), Track::Fixed(4.0)])
+                .gap(0.0, 1.0)
+                .with_cell(GridCell::new(Fill).at(0, 1).span(0, 0)),
+        );
+        assert_eq!(tree.children[0].view.size.get().width, 4.0);
+        assert_eq!(tree.children[0].view.offset.get(), Offset::new(4.0, 0.0));
+    }
+
+    #[test]
+    fn fill_width_child_takes_the_fraction() {
+        let tree = place(
+            Grid::new()
+                .columns([Track::Fraction(1.0), Track::Auto])
+                .with_child(0, 0, Fill)
+                .with_child(0, 1, Text::new("label")),
+        );
+        assert_eq!(tree.children[0].view.size.get().width, 75.0);
+        assert_eq!(tree.children[1].view.offset.get(), Offset::new(75.0, 0.0));
+    }
+}
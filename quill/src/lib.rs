@@ -10,11 +10,28 @@ use std::fmt::Debug;
 use std::rc::Rc;
 
 use ptree::{item::StringItem, print_tree, TreeBuilder};
+use serde::{Deserialize, Serialize};
 
 pub use quill_core::*;
 use quill_derive::*;
 
+// Lets the code generated by the derive name `::quill` inside this crate too.
+extern crate self as quill;
+
 // mod smooth;
+mod flex;
+mod grid;
+mod overlay;
+mod profile;
+mod snapshot;
+mod virtual_list;
+
+pub use flex::*;
+pub use grid::*;
+pub use overlay::*;
+pub use profile::*;
+pub use snapshot::*;
+pub use virtual_list::*;
 
 /*
 Hydrate ViewTree.
@@ -34,7 +51,7 @@ Hydrate ViewTree.
 // State<X>
 // lens: State<X>, X-> &mut Y -> State<Y>
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize)]
 pub struct Key(pub u32);
 
 impl Key {
@@ -53,10 +70,19 @@ impl Default for Key {
 
 pub type Store = HashMap<Key, ViewTree>;
 
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum Event {
     MousePress(MouseButton, f64, f64),
+    MouseRelease(f64, f64),
+    MouseHold(f64, f64),
+    MouseMove(f64, f64),
+    KeyPress(KeyCode, Modifiers),
+    /// Sent to every view once per frame with the seconds elapsed since the
+    /// run loop started, for animations.
+    Frame(f64),
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum MouseButton {
     Left,
     Right,
@@ -65,6 +91,44 @@ pub enum MouseButton {
     WheelDown,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum KeyCode {
+    Char(char),
+    Backspace,
+    Delete,
+    Enter,
+    Tab,
+    BackTab,
+    Esc,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Insert,
+    F(u8),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+}
+
+impl Modifiers {
+    pub fn none() -> Modifiers {
+        Modifiers::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !(self.shift || self.ctrl || self.alt)
+    }
+}
+
 pub trait View: Hydrate + AsAny + Debug + 'static {
     fn body(&self) -> AnyView {
         // AnyView(Box::new(EmptyView))
@@ -80,7 +144,47 @@ pub trait View: Hydrate + AsAny + Debug + 'static {
         vec![self.body()]
     }
 
-    fn event(&self, _size: Size, _offset: Offset, _children: &[ViewTree], _event: &Event) {}
+    /// Handle an event. Mouse events are broadcast to every view, key events
+    /// start at the focused view and bubble towards the root until a view
+    /// returns `true`.
+    fn event(&self, _size: Size, _offset: Offset, _children: &[ViewTree], _event: &Event) -> bool {
+        false
+    }
+
+    /// Whether the view can receive keyboard focus.
+    fn focusable(&self) -> bool {
+        false
+    }
+
+    /// Called by `Focus` when the view gains or loses keyboard focus.
+    fn set_focus(&self, _focused: bool) {}
+
+    /// Keep the subtrees of children that disappear from the body, and
+    /// hydrate them again when a child with the same key comes back. Used by
+    /// containers that show one child at a time. Children must have distinct
+    /// keys.
+    fn retain_children(&self) -> bool {
+        false
+    }
+
+    /// Whether the view captures input while it is part of the tree. See
+    /// `ViewTree::modal`.
+    fn is_modal(&self) -> bool {
+        false
+    }
+
+    /// Where an `Overlay` places the view when it is one of its layers.
+    fn placement(&self) -> Placement {
+        Placement::Center
+    }
+
+    /// The area children are clipped to, given the bounds of the view.
+    /// Containers that let children overflow on purpose, or that place them
+    /// outside their own size, return `None`; the children are then only
+    /// clipped by the ancestors of the view.
+    fn clip_children(&self, bounds: Rect) -> Option<Rect> {
+        Some(bounds)
+    }
 
     fn layout(&self, children: &[ViewTree], constraint: Constraint) -> Size {
         if let [child] = children {
@@ -92,6 +196,27 @@ pub trait View: Hydrate + AsAny + Debug + 'static {
         }
     }
 
+    /// The smallest (`Min*`) or natural (`Max*`) width of the view given a
+    /// height, or height given a width. Meant for containers that negotiate
+    /// sizes before laying out their children; ask through `ViewTree`, which
+    /// caches the answer.
+    ///
+    /// Measuring must not change the view or its children. By default a
+    /// view with a single child answers for its child, one with several
+    /// children for the largest of them, and a view without children is laid
+    /// out with the measured axis unbounded. Views that place their children
+    /// differently, or can shrink below their natural size, override this.
+    fn intrinsic(&self, children: &[ViewTree], kind: Intrinsic, extent: f64) -> f64 {
+        match children {
+            [] => kind.of(self.layout(children, kind.constraint(extent))),
+            [child] => child.intrinsic(kind, extent),
+            _ => children
+                .iter()
+                .map(|child| child.intrinsic(kind, extent))
+                .fold(0.0, f64::max),
+        }
+    }
+
     fn set_offset(&self, children: &[ViewTree], offset: Offset) {
         for child in children {
             child.set_offset(offset)
@@ -99,6 +224,92 @@ pub trait View: Hydrate + AsAny + Debug + 'static {
     }
 }
 
+/// One of the intrinsic sizes of `View`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Intrinsic {
+    MinWidth,
+    MaxWidth,
+    MinHeight,
+    MaxHeight,
+}
+
+impl Intrinsic {
+    pub fn is_width(self) -> bool {
+        matches!(self, Intrinsic::MinWidth | Intrinsic::MaxWidth)
+    }
+
+    pub fn is_min(self) -> bool {
+        matches!(self, Intrinsic::MinWidth | Intrinsic::MinHeight)
+    }
+
+    /// A constraint with `extent` on the other axis and no limit on the
+    /// measured one.
+    pub fn constraint(self, extent: f64) -> Constraint {
+        if self.is_width() {
+            Constraint::new(f64::INFINITY, extent)
+        } else {
+            Constraint::new(extent, f64::INFINITY)
+        }
+    }
+
+    /// The measured side of `size`.
+    pub fn of(self, size: Size) -> f64 {
+        if self.is_width() {
+            size.width
+        } else {
+            size.height
+        }
+    }
+}
+
+/// `size` if it is finite, else `limit` if that is, else 0. Views that fill
+/// the space they are given measure as infinite; containers sizing tracks or
+/// lines from intrinsic sizes give them the available space instead.
+pub fn bounded(size: f64, limit: f64) -> f64 {
+    if size.is_finite() {
+        size
+    } else if limit.is_finite() {
+        limit
+    } else {
+        0.0
+    }
+}
+
+thread_local! {
+    // Bumped whenever a tree is hydrated, which drops every cached intrinsic
+    // size.
+    static GENERATION: Cell<u64> = const { Cell::new(0) };
+    // Set by layouts that changed state a body depends on.
+    static REBUILD: Cell<bool> = const { Cell::new(false) };
+    // The time of the last `Event::Frame` delivered to a tree.
+    static CLOCK: Cell<f64> = const { Cell::new(0.0) };
+}
+
+/// Ask for the tree to be hydrated and laid out again before it is drawn.
+/// For views whose children depend on their own layout, such as the rows of
+/// a `VirtualList` that fit its height.
+pub fn request_rebuild() {
+    REBUILD.with(|rebuild| rebuild.set(true));
+}
+
+/// Whether a rebuild was requested since the last call.
+pub fn take_rebuild() -> bool {
+    REBUILD.with(|rebuild| rebuild.replace(false))
+}
+
+/// Seconds since the start, as of the last `Event::Frame` delivered to a
+/// tree. Unlike the wall clock, it follows a replay.
+pub fn frame_time() -> f64 {
+    CLOCK.with(Cell::get)
+}
+
+/// Intrinsic sizes of a view, keyed by kind and the bits of the given extent.
+#[derive(Debug, Default)]
+struct IntrinsicCache {
+    generation: u64,
+    sizes: HashMap<(Intrinsic, u64), f64>,
+}
+
 pub trait AsAny {
     fn as_any(&self) -> &dyn Any;
     fn as_any_mut(&mut self) -> &mut dyn Any;
@@ -196,6 +407,23 @@ impl View for Stack {
         my_size
     }
 
+    // Children are stacked vertically, each with the full width.
+    fn intrinsic(&self, children: &[ViewTree], kind: Intrinsic, extent: f64) -> f64 {
+        let sizes = children.iter().map(|child| {
+            let extent = if kind.is_width() {
+                f64::INFINITY
+            } else {
+                extent
+            };
+            child.intrinsic(kind, extent)
+        });
+        if kind.is_width() {
+            sizes.fold(0.0, f64::max)
+        } else {
+            sizes.sum()
+        }
+    }
+
     fn set_offset(&self, children: &[ViewTree], mut offset: Offset) {
         for child in children {
             let size = child.view.size.get();
@@ -218,67 +446,112 @@ pub trait Hydrate {
         false
     }
 
+    /// Copy the props `is_same` ignores, e.g. callbacks. Called instead of
+    /// `hydrate` when the view is clean and the same, so that they never go
+    /// stale.
+    fn refresh(&mut self, _other: &Self)
+    where
+        Self: Sized,
+    {
+    }
+
     fn is_dirty(&self) -> bool {
         true
     }
 
     fn clean(&self) {}
+
+    /// The `State` fields by name, for debugging tools. The derive lists
+    /// the `State` fields whose values are `Debug`.
+    fn states(&self) -> Vec<(&'static str, &dyn InspectState)> {
+        Vec::new()
+    }
 }
 
+// (new views, deleted subtrees, updated subtrees), tagged with the position
+// of the view in the new body.
+type Diff = (
+    Vec<(usize, AnyView)>,
+    Vec<ViewTree>,
+    Vec<(usize, AnyView, ViewTree)>,
+);
+
 #[derive(Clone)]
 pub struct ViewTree {
     pub view: AnyView,
     pub children: Vec<ViewTree>,
+    /// Subtrees removed from the body of a view that retains its children.
+    pub retained: Store,
 }
 
 impl ViewTree {
     #[track_caller]
     pub fn new(root: AnyView) -> ViewTree {
-        let children = root
-            .borrow()
-            .children()
+        let children = ViewTree::body(&root)
             .into_iter()
             .map(ViewTree::new)
             .collect();
         ViewTree {
             view: root,
             children,
+            retained: Store::new(),
         }
     }
 
     pub fn perform_hydrate_dirty(&mut self) {
-        let ViewTree { view, children } = self;
+        let ViewTree {
+            view,
+            children,
+            retained,
+        } = self;
         if view.borrow().is_dirty() {
-            // eprintln!("Hydrating dirty: {:?}", view);
-            let new_children = view.borrow().children();
-            for (new_root, old_tree) in new_children.into_iter().zip(children.iter_mut()) {
-                old_tree.perform_hydrate(new_root);
+            ViewTree::hydrate_children(view, children, retained);
+        } else {
+            view.record(|profile| profile.skipped += 1);
+            for child in children.iter_mut() {
+                child.perform_hydrate_dirty();
             }
         }
     }
 
+    /// The children of `view`, counted and timed as a body evaluation.
+    fn body(view: &AnyView) -> Vec<AnyView> {
+        let (children, time) = profile::timed(|| view.borrow().children());
+        view.record(|profile| {
+            profile.bodies += 1;
+            profile.body_time += time;
+        });
+        children
+    }
+
     pub fn perform_hydrate(&mut self, root: AnyView) {
-        let ViewTree { view, children } = self;
+        GENERATION.with(|generation| generation.set(generation.get() + 1));
+        if self.view.view_type() != root.view_type() {
+            // Same key but a different kind of view: nothing to hydrate.
+            *self = ViewTree::new(root);
+            return;
+        }
+        let ViewTree {
+            view,
+            children,
+            retained,
+        } = self;
         // eprintln!("Hydrating: {:?}", view);
         if !view.borrow().is_dirty() && view.is_same.clone()(view, &root) {
             // eprintln!("Hydrating clean: {:?} {:?}", view, root);
+            view.refresh_any(root);
+            view.record(|profile| profile.skipped += 1);
             for child in children.iter_mut() {
                 child.perform_hydrate_dirty();
             }
         } else {
             // eprintln!("Hydrating modified: {:?} {:?}", view, root);
-            view.hydrate_any(root);
-            let new_children = view.borrow().children();
-            let prev_children = std::mem::take(children);
-            let (new, _del, upd) = ViewTree::diff(new_children, prev_children);
-            for elt in new.into_iter() {
-                children.push(ViewTree::new(elt));
-            }
-            for (new_root, mut old_tree) in upd.into_iter() {
-                old_tree.perform_hydrate(new_root);
-                children.push(old_tree);
-            }
-            children.sort_unstable_by_key(|tree| tree.view.key);
+            let ((), time) = profile::timed(|| view.hydrate_any(root));
+            view.record(|profile| {
+                profile.hydrations += 1;
+                profile.hydrate_time += time;
+            });
+            ViewTree::hydrate_children(view, children, retained);
         }
         // Diff:
         //   New: [AnyView]
@@ -296,10 +569,40 @@ impl ViewTree {
         // }
     }
 
-    fn diff(
-        views: Vec<AnyView>,
-        children: Vec<ViewTree>,
-    ) -> (Vec<AnyView>, Vec<ViewTree>, Vec<(AnyView, ViewTree)>) {
+    /// Rebuild the body of `view` and reconcile it with `children`.
+    fn hydrate_children(view: &AnyView, children: &mut Vec<ViewTree>, retained: &mut Store) {
+        // Children are matched by key. Views sharing a key, such as the
+        // ones pushed in a loop, are matched in order. The result keeps
+        // the order of the new body.
+        let mut new_children: Vec<(usize, AnyView)> =
+            ViewTree::body(view).into_iter().enumerate().collect();
+        new_children.sort_by_key(|(_, view)| view.key);
+        let mut prev_children = std::mem::take(children);
+        prev_children.sort_by_key(|tree| tree.view.key);
+        let (mut new, del, mut upd) = ViewTree::diff(new_children, prev_children);
+        if view.borrow().retain_children() {
+            new.retain(|(idx, elt)| match retained.remove(&elt.key) {
+                Some(old_tree) => {
+                    upd.push((*idx, elt.clone(), old_tree));
+                    false
+                }
+                None => true,
+            });
+            retained.extend(del.into_iter().map(|tree| (tree.view.key, tree)));
+        }
+        let mut slots: Vec<Option<ViewTree>> = Vec::new();
+        slots.resize_with(new.len() + upd.len(), || None);
+        for (idx, elt) in new.into_iter() {
+            slots[idx] = Some(ViewTree::new(elt));
+        }
+        for (idx, new_root, mut old_tree) in upd.into_iter() {
+            old_tree.perform_hydrate(new_root);
+            slots[idx] = Some(old_tree);
+        }
+        children.extend(slots.into_iter().flatten());
+    }
+
+    fn diff(views: Vec<(usize, AnyView)>, children: Vec<ViewTree>) -> Diff {
         let mut views_iter = views.into_iter().peekable();
         let mut child_iter = children.into_iter().peekable();
         let mut new = Vec::new();
@@ -319,7 +622,7 @@ impl ViewTree {
                     // child_iter.next();
                 }
                 (Some(view), Some(child)) => {
-                    match view.key.cmp(&child.view.key) {
+                    match view.1.key.cmp(&child.view.key) {
                         Ordering::Less => {
                             new.push(view.clone());
                             views_iter.next();
@@ -332,7 +635,7 @@ impl ViewTree {
                         }
                         Ordering::Equal => {
                             if let Some(child) = child_iter.next() {
-                                upd.push((view.clone(), child));
+                                upd.push((view.0, view.1.clone(), child));
                                 views_iter.next();
                             }
                         }
@@ -344,12 +647,71 @@ impl ViewTree {
     }
 
     pub fn layout(&self, constraint: Constraint) {
-        let size = self.view.borrow().layout(&self.children, constraint);
+        let (size, time) =
+            profile::timed_layout(|| self.view.borrow().layout(&self.children, constraint));
+        self.view.record(|profile| {
+            profile.layouts += 1;
+            profile.layout_time += time;
+        });
         self.view.size.set(size);
+        self.view.constraint.set(constraint);
+    }
+
+    /// The intrinsic size of the view, computed at most once per hydration.
+    pub fn intrinsic(&self, kind: Intrinsic, extent: f64) -> f64 {
+        let generation = GENERATION.with(Cell::get);
+        let key = (kind, extent.to_bits());
+        {
+            let mut cache = self.view.intrinsics.borrow_mut();
+            if cache.generation != generation {
+                cache.generation = generation;
+                cache.sizes.clear();
+            }
+            if let Some(size) = cache.sizes.get(&key) {
+                return *size;
+            }
+        }
+        let (size, time) =
+            profile::timed_layout(|| self.view.borrow().intrinsic(&self.children, kind, extent));
+        self.view.record(|profile| {
+            profile.measures += 1;
+            profile.measure_time += time;
+        });
+        self.view.intrinsics.borrow_mut().sizes.insert(key, size);
+        size
+    }
+
+    pub fn min_intrinsic_width(&self, height: f64) -> f64 {
+        self.intrinsic(Intrinsic::MinWidth, height)
+    }
+
+    pub fn max_intrinsic_width(&self, height: f64) -> f64 {
+        self.intrinsic(Intrinsic::MaxWidth, height)
+    }
+
+    pub fn min_intrinsic_height(&self, width: f64) -> f64 {
+        self.intrinsic(Intrinsic::MinHeight, width)
     }
 
+    pub fn max_intrinsic_height(&self, width: f64) -> f64 {
+        self.intrinsic(Intrinsic::MaxHeight, width)
+    }
+
+    /// Place the view and its children. Also passes the clip rect down: each
+    /// child gets the clip of this view, narrowed by `View::clip_children`.
     pub fn set_offset(&self, offset: Offset) {
         self.view.offset.set(offset);
+        let mut clip = self.view.clip.get();
+        if let Some(rect) = self.view.borrow().clip_children(self.view.rect()) {
+            // An empty rect when nothing is left, rather than no clip.
+            clip = Some(
+                clip.map_or(Some(rect), |clip| clip.intersect(&rect))
+                    .unwrap_or_default(),
+            );
+        }
+        for child in &self.children {
+            child.view.clip.set(clip);
+        }
         self.view.borrow().set_offset(&self.children, offset);
     }
 
@@ -358,7 +720,7 @@ impl ViewTree {
     }
 
     pub fn tree(&self) -> StringItem {
-        let ViewTree { view, children } = self;
+        let ViewTree { view, children, .. } = self;
         let mut builder = TreeBuilder::new(format!("{:?}", view.view.borrow()));
         for child in children {
             child.mk_tree(&mut builder);
@@ -368,7 +730,7 @@ impl ViewTree {
 
     fn mk_tree(&self, builder: &mut TreeBuilder) {
         if (*self.view.borrow()).type_id() != TypeId::of::<EmptyView>() {
-            let ViewTree { view, children } = self;
+            let ViewTree { view, children, .. } = self;
             builder.begin_child(format!("{:?}: {:?}", view.key, view.view.borrow()));
             for child in children {
                 child.mk_tree(builder);
@@ -389,15 +751,83 @@ impl ViewTree {
         out
     }
 
-    pub fn event(&self, event: &Event) {
+    pub fn event(&self, event: &Event) -> bool {
+        if let Event::Frame(time) = *event {
+            CLOCK.with(|clock| clock.set(time));
+        }
+        let mut handled = self.handle(event);
+        for child in self.children.iter() {
+            handled |= child.event(event);
+        }
+        handled
+    }
+
+    /// Deliver an event to `target` and bubble it up through its ancestors
+    /// until it is handled. Returns `false` if nobody handled it or if
+    /// `target` isn't part of this tree.
+    pub fn dispatch(&self, target: &AnyView, event: &Event) -> bool {
+        self.dispatch_path(target, event).unwrap_or(false)
+    }
+
+    fn dispatch_path(&self, target: &AnyView, event: &Event) -> Option<bool> {
+        if &self.view == target {
+            return Some(self.handle(event));
+        }
+        for child in self.children.iter() {
+            if let Some(handled) = child.dispatch_path(target, event) {
+                return Some(handled || self.handle(event));
+            }
+        }
+        None
+    }
+
+    fn handle(&self, event: &Event) -> bool {
         self.view.borrow().event(
             self.view.size.get(),
             self.view.offset.get(),
             &self.children,
             event,
-        );
-        for child in self.children.iter() {
-            child.event(event);
+        )
+    }
+
+    /// The topmost modal subtree: the innermost one, and among siblings the
+    /// last one, which is drawn above the others. Input should be limited to
+    /// it while there is one.
+    pub fn modal(&self) -> Option<&ViewTree> {
+        self.children
+            .iter()
+            .rev()
+            .find_map(|child| child.modal())
+            .or_else(|| self.view.borrow().is_modal().then_some(self))
+    }
+
+    pub fn contains(&self, target: &AnyView) -> bool {
+        &self.view == target || self.children.iter().any(|child| child.contains(target))
+    }
+
+    /// Focusable views in tree order.
+    pub fn focusable(&self) -> Vec<AnyView> {
+        self.flatten()
+            .into_iter()
+            .filter(|view| view.borrow().focusable())
+            .collect()
+    }
+
+    /// The innermost focusable view whose layout covers the point.
+    pub fn focusable_at(&self, x: f64, y: f64) -> Option<AnyView> {
+        let inner = self
+            .children
+            .iter()
+            .rev()
+            .find_map(|child| child.focusable_at(x, y));
+        if inner.is_some() {
+            return inner;
+        }
+        let visible = self.view.visible_rect();
+        if visible.is_some_and(|rect| rect.contains(x, y)) && self.view.borrow().focusable() {
+            Some(self.view.clone())
+        } else {
+            None
         }
     }
 
@@ -409,14 +839,27 @@ impl ViewTree {
     }
 }
 
+type HydrateFn = Rc<dyn Fn(&AnyView, &AnyView)>;
+type IsSameFn = Rc<dyn Fn(&AnyView, &AnyView) -> bool>;
+
 #[derive(Clone)]
 pub struct AnyView {
     key: Key,
     pub size: Rc<Cell<Size>>,
     pub offset: Rc<Cell<Offset>>,
+    /// The constraint of the last layout. Only `ViewTree::layout` sets it;
+    /// measuring intrinsic sizes leaves it alone.
+    pub constraint: Rc<Cell<Constraint>>,
+    /// The area the view may draw in, from the last `ViewTree::set_offset`.
+    /// `None` when nothing clips it.
+    pub clip: Rc<Cell<Option<Rect>>>,
+    intrinsics: Rc<RefCell<IntrinsicCache>>,
+    profile: Rc<Cell<Profile>>,
     view: Rc<RefCell<dyn View>>,
-    hydrate: Rc<dyn Fn(&AnyView, &AnyView)>,
-    is_same: Rc<dyn Fn(&AnyView, &AnyView) -> bool>,
+    type_name: &'static str,
+    hydrate: HydrateFn,
+    refresh: HydrateFn,
+    is_same: IsSameFn,
 }
 
 impl PartialEq for AnyView {
@@ -442,7 +885,12 @@ impl AnyView {
             key: Key::new(),
             size: Rc::new(Cell::new(Size::zero())),
             offset: Rc::new(Cell::new(Offset::zero())),
+            constraint: Rc::new(Cell::new(Constraint::unbounded())),
+            clip: Rc::new(Cell::new(None)),
+            intrinsics: Rc::new(RefCell::new(IntrinsicCache::default())),
+            profile: Rc::new(Cell::new(Profile::default())),
             view: Rc::new(RefCell::new(view)),
+            type_name: std::any::type_name::<V>(),
             hydrate: Rc::new(|a, b| {
                 if let Some(mut a) = a.downcast_mut::<V>() {
                     if let Some(b) = b.downcast_ref::<V>() {
@@ -450,6 +898,13 @@ impl AnyView {
                     }
                 }
             }),
+            refresh: Rc::new(|a, b| {
+                if let Some(mut a) = a.downcast_mut::<V>() {
+                    if let Some(b) = b.downcast_ref::<V>() {
+                        a.refresh(&b)
+                    }
+                }
+            }),
             is_same: Rc::new(|a, b| {
                 if let Some(a) = a.downcast_ref::<V>() {
                     if let Some(b) = b.downcast_ref::<V>() {
@@ -488,11 +943,83 @@ impl AnyView {
         self.view.borrow()
     }
 
+    pub fn key(&self) -> Key {
+        self.key
+    }
+
+    /// The area from the last layout.
+    pub fn rect(&self) -> Rect {
+        Rect::new(self.offset.get(), self.size.get())
+    }
+
+    /// Whether the last layout returned a size outside its constraint.
+    pub fn overflows(&self) -> bool {
+        let size = self.size.get();
+        self.constraint.get().constrain(size) != size
+    }
+
+    /// The part of `rect` that is not clipped away, if any.
+    pub fn visible_rect(&self) -> Option<Rect> {
+        match self.clip.get() {
+            Some(clip) => self.rect().intersect(&clip),
+            None => Some(self.rect()),
+        }
+    }
+
+    /// Replace the call-site key, e.g. to identify rows built in a loop.
+    pub fn with_key(mut self, key: Key) -> Self {
+        self.key = key;
+        self
+    }
+
+    pub fn view_type(&self) -> TypeId {
+        (*self.view.borrow()).as_any().type_id()
+    }
+
+    /// The full path of the view type, e.g. `quill::Stack`.
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// `type_name` without module paths, e.g. `Map<Stack>` for
+    /// `quill::Map<quill::Stack>`.
+    pub fn short_type_name(&self) -> String {
+        let mut out = String::new();
+        let mut word = String::new();
+        for c in self.type_name.chars() {
+            if c.is_alphanumeric() || c == '_' || c == ':' {
+                word.push(c);
+            } else {
+                out.push_str(word.rsplit("::").next().unwrap_or(""));
+                word.clear();
+                out.push(c);
+            }
+        }
+        out.push_str(word.rsplit("::").next().unwrap_or(""));
+        out
+    }
+
+    /// What the view cost so far, see `ViewTree::profile_report`.
+    pub fn profile(&self) -> Profile {
+        self.profile.get()
+    }
+
+    fn record(&self, f: impl FnOnce(&mut Profile)) {
+        let mut profile = self.profile.get();
+        f(&mut profile);
+        self.profile.set(profile);
+    }
+
     pub fn hydrate_any(&self, other: AnyView) {
         let cb = self.hydrate.clone();
         cb(self, &other);
         // self.view.borrow_mut().hydrate_single(other);
     }
+
+    pub fn refresh_any(&self, other: AnyView) {
+        let cb = self.refresh.clone();
+        cb(self, &other);
+    }
 }
 
 pub trait ToAnyView: View + Sized {
@@ -503,38 +1030,62 @@ pub trait ToAnyView: View + Sized {
 }
 impl<X: View + Sized> ToAnyView for X {}
 
-#[derive(Clone, PartialEq)]
-pub struct State<X: Copy> {
+#[derive(PartialEq)]
+pub struct State<X> {
     dirty: Rc<Cell<bool>>,
-    value: Rc<Cell<X>>,
+    value: Rc<RefCell<X>>,
+}
+
+impl<X> Clone for State<X> {
+    fn clone(&self) -> Self {
+        State {
+            dirty: self.dirty.clone(),
+            value: self.value.clone(),
+        }
+    }
 }
 
-impl<X: Debug + Copy> std::fmt::Debug for State<X> {
+impl<X: Debug> std::fmt::Debug for State<X> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let name = if self.dirty.get() {
             "StateDirty"
         } else {
             "State"
         };
-        f.debug_tuple(name).field(&self.value.get()).finish()
+        f.debug_tuple(name).field(&self.value.borrow()).finish()
     }
 }
 
-impl<X: Copy> State<X> {
+impl<X> State<X> {
     pub fn new(value: X) -> State<X> {
         State {
             dirty: Rc::new(Cell::new(true)),
-            value: Rc::new(Cell::new(value)),
+            value: Rc::new(RefCell::new(value)),
         }
     }
 
-    pub fn get(&self) -> X {
-        self.value.get()
+    pub fn get(&self) -> X
+    where
+        X: Clone,
+    {
+        self.value.borrow().clone()
     }
 
     pub fn set(&self, value: X) {
-        self.value.set(value);
+        *self.value.borrow_mut() = value;
+        self.dirty.set(true);
+    }
+
+    /// Borrow the value without cloning it.
+    pub fn with<R>(&self, f: impl FnOnce(&X) -> R) -> R {
+        f(&self.value.borrow())
+    }
+
+    /// Modify the value in place and mark the state as dirty.
+    pub fn update<R>(&self, f: impl FnOnce(&mut X) -> R) -> R {
+        let ret = f(&mut self.value.borrow_mut());
         self.dirty.set(true);
+        ret
     }
 
     pub fn clean(&self) {
@@ -546,6 +1097,197 @@ impl<X: Copy> State<X> {
     }
 }
 
+/// A `State` of any type, as seen by debugging tools.
+pub trait InspectState {
+    /// The value, formatted with `Debug`.
+    fn debug(&self) -> String;
+
+    /// Replace the value with one parsed from `text`. Only strings, chars,
+    /// booleans and numbers can be edited; quotes around strings and chars
+    /// are optional.
+    fn edit(&self, text: &str) -> Result<(), String>;
+}
+
+impl<X: Debug + 'static> InspectState for State<X> {
+    fn debug(&self) -> String {
+        format!("{:?}", self.value.borrow())
+    }
+
+    fn edit(&self, text: &str) -> Result<(), String> {
+        fn parse<T: std::str::FromStr + 'static>(
+            value: &mut dyn Any,
+            text: &str,
+        ) -> Option<Result<(), String>> {
+            let value = value.downcast_mut::<T>()?;
+            Some(match text.parse() {
+                Ok(parsed) => {
+                    *value = parsed;
+                    Ok(())
+                }
+                Err(_) => Err(format!("not a valid {}", std::any::type_name::<T>())),
+            })
+        }
+        let unquoted = |quote| {
+            text.strip_prefix(quote)
+                .and_then(|text| text.strip_suffix(quote))
+                .unwrap_or(text)
+        };
+        let result = {
+            let mut value = self.value.borrow_mut();
+            let value: &mut dyn Any = &mut *value;
+            parse::<String>(value, unquoted('"'))
+                .or_else(|| parse::<char>(value, unquoted('\'')))
+                .or_else(|| parse::<bool>(value, text))
+                .or_else(|| parse::<i8>(value, text))
+                .or_else(|| parse::<i16>(value, text))
+                .or_else(|| parse::<i32>(value, text))
+                .or_else(|| parse::<i64>(value, text))
+                .or_else(|| parse::<isize>(value, text))
+                .or_else(|| parse::<u8>(value, text))
+                .or_else(|| parse::<u16>(value, text))
+                .or_else(|| parse::<u32>(value, text))
+                .or_else(|| parse::<u64>(value, text))
+                .or_else(|| parse::<usize>(value, text))
+                .or_else(|| parse::<f32>(value, text))
+                .or_else(|| parse::<f64>(value, text))
+                .unwrap_or_else(|| Err(format!("cannot edit {}", std::any::type_name::<X>())))
+        };
+        if result.is_ok() {
+            self.dirty.set(true);
+        }
+        result
+    }
+}
+
+/// Used by the derive to list only the `State` fields that implement
+/// `InspectState`, i.e. whose values are `Debug`, without requiring it of the
+/// others.
+#[doc(hidden)]
+pub mod __private {
+    use super::InspectState;
+
+    pub struct Inspect<'a, X>(pub &'a X);
+
+    pub trait InspectDebug<'a> {
+        fn inspect(&self) -> Option<&'a dyn InspectState>;
+    }
+
+    impl<'a, X: InspectState> InspectDebug<'a> for Inspect<'a, X> {
+        fn inspect(&self) -> Option<&'a dyn InspectState> {
+            Some(self.0)
+        }
+    }
+
+    // Picked by `(&Inspect(x)).inspect()` only when the impl above doesn't
+    // apply, since it takes one more reference.
+    pub trait InspectOther<'a> {
+        fn inspect(&self) -> Option<&'a dyn InspectState>;
+    }
+
+    impl<'a, X> InspectOther<'a> for &Inspect<'a, X> {
+        fn inspect(&self) -> Option<&'a dyn InspectState> {
+            None
+        }
+    }
+}
+
+/// Keeps track of which view receives keyboard events.
+#[derive(Default)]
+pub struct Focus {
+    current: Option<AnyView>,
+    // The focus from before a modal opened, if one is open.
+    saved: Option<Option<AnyView>>,
+}
+
+impl Focus {
+    pub fn new() -> Focus {
+        Focus::default()
+    }
+
+    pub fn current(&self) -> Option<&AnyView> {
+        self.current.as_ref()
+    }
+
+    pub fn is_focused(&self, view: &AnyView) -> bool {
+        self.current.as_ref() == Some(view)
+    }
+
+    pub fn set(&mut self, view: Option<AnyView>) {
+        if self.current == view {
+            return;
+        }
+        if let Some(old) = self.current.take() {
+            old.borrow().set_focus(false);
+        }
+        if let Some(new) = &view {
+            new.borrow().set_focus(true);
+        }
+        self.current = view;
+    }
+
+    /// Move focus to the next focusable view, wrapping around.
+    pub fn next(&mut self, tree: &ViewTree) {
+        self.step(tree, 1)
+    }
+
+    /// Move focus to the previous focusable view, wrapping around.
+    pub fn prev(&mut self, tree: &ViewTree) {
+        self.step(tree, -1)
+    }
+
+    fn step(&mut self, tree: &ViewTree, delta: isize) {
+        let views = tree.focusable();
+        if views.is_empty() {
+            return self.set(None);
+        }
+        let len = views.len() as isize;
+        let next = match self
+            .current
+            .as_ref()
+            .and_then(|cur| views.iter().position(|view| view == cur))
+        {
+            Some(idx) => (idx as isize + delta).rem_euclid(len),
+            None if delta < 0 => len - 1,
+            None => 0,
+        };
+        self.set(Some(views[next as usize].clone()));
+    }
+
+    /// Keep the focus inside the modal subtree of `tree` while there is one,
+    /// and give it back to the view that had it once the modal is gone.
+    pub fn trap(&mut self, tree: &ViewTree) {
+        match tree.modal() {
+            Some(modal) => {
+                if self.current.as_ref().is_some_and(|cur| modal.contains(cur)) {
+                    return;
+                }
+                if self.saved.is_none() {
+                    self.saved = Some(self.current.clone());
+                }
+                self.set(modal.focusable().into_iter().next());
+            }
+            None => {
+                if let Some(saved) = self.saved.take() {
+                    let saved =
+                        saved.filter(|view| tree.contains(view) && view.borrow().focusable());
+                    if self.current.is_none() {
+                        self.set(saved);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Drop the focus if the focused view has been removed from the tree.
+    pub fn sync(&mut self, tree: &ViewTree) {
+        if let Some(cur) = &self.current {
+            if !tree.contains(cur) || !cur.borrow().focusable() {
+                self.set(None);
+            }
+        }
+    }
+}
+
 // pub trait StateObject: Any + Clone + Default {}
 
 // pub struct Context {
@@ -583,3 +1325,179 @@ impl<X: Copy> State<X> {
 //         }
 //     }
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq, Hydrate)]
+    struct Leaf(String);
+
+    impl View for Leaf {
+        fn children(&self) -> Vec<AnyView> {
+            Vec::new()
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq, Hydrate)]
+    struct Other;
+
+    impl View for Other {
+        fn children(&self) -> Vec<AnyView> {
+            Vec::new()
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq, Hydrate)]
+    struct Parent(Vec<AnyView>);
+
+    impl View for Parent {
+        fn children(&self) -> Vec<AnyView> {
+            self.0.clone()
+        }
+    }
+
+    fn leaf(text: &str, key: u32) -> AnyView {
+        Leaf(text.to_string()).any_view().with_key(Key(key))
+    }
+
+    fn parent(children: Vec<AnyView>) -> AnyView {
+        Parent(children).any_view()
+    }
+
+    fn texts(tree: &ViewTree) -> Vec<String> {
+        tree.children
+            .iter()
+            .map(|child| child.view.downcast_ref::<Leaf>().unwrap().0.clone())
+            .collect()
+    }
+
+    fn nodes(tree: &ViewTree) -> Vec<AnyView> {
+        tree.children
+            .iter()
+            .map(|child| child.view.clone())
+            .collect()
+    }
+
+    #[test]
+    fn reorder_keeps_the_nodes() {
+        let mut tree = ViewTree::new(parent(vec![leaf("a", 1), leaf("b", 2), leaf("c", 3)]));
+        let before = nodes(&tree);
+        tree.perform_hydrate(parent(vec![leaf("c", 3), leaf("a", 1), leaf("b", 2)]));
+        assert_eq!(texts(&tree), ["c", "a", "b"]);
+        assert_eq!(
+            nodes(&tree),
+            [before[2].clone(), before[0].clone(), before[1].clone()]
+        );
+    }
+
+    #[test]
+    fn insert_builds_only_the_new_view() {
+        let mut tree = ViewTree::new(parent(vec![leaf("a", 1), leaf("b", 2)]));
+        let before = nodes(&tree);
+        tree.perform_hydrate(parent(vec![leaf("a", 1), leaf("x", 4), leaf("b", 2)]));
+        assert_eq!(texts(&tree), ["a", "x", "b"]);
+        let after = nodes(&tree);
+        assert_eq!(after[0], before[0]);
+        assert_eq!(after[2], before[1]);
+        assert!(!before.contains(&after[1]));
+    }
+
+    #[test]
+    fn delete_drops_the_node() {
+        let mut tree = ViewTree::new(parent(vec![leaf("a", 1), leaf("b", 2), leaf("c", 3)]));
+        let before = nodes(&tree);
+        tree.perform_hydrate(parent(vec![leaf("a", 1), leaf("c", 3)]));
+        assert_eq!(texts(&tree), ["a", "c"]);
+        assert_eq!(nodes(&tree), [before[0].clone(), before[2].clone()]);
+    }
+
+    #[test]
+    fn duplicate_keys_match_in_order() {
+        let mut tree = ViewTree::new(parent(vec![leaf("a", 7), leaf("b", 7), leaf("c", 7)]));
+        let before = nodes(&tree);
+        tree.perform_hydrate(parent(vec![leaf("x", 7), leaf("y", 7)]));
+        assert_eq!(texts(&tree), ["x", "y"]);
+        assert_eq!(nodes(&tree), [before[0].clone(), before[1].clone()]);
+
+        tree.perform_hydrate(parent(vec![leaf("x", 7), leaf("y", 7), leaf("z", 7)]));
+        assert_eq!(texts(&tree), ["x", "y", "z"]);
+        assert_eq!(nodes(&tree)[..2], before[..2]);
+    }
+
+    #[test]
+    fn type_change_replaces_the_node() {
+        let mut tree = ViewTree::new(parent(vec![leaf("a", 1), leaf("b", 2)]));
+        let before = nodes(&tree);
+        tree.perform_hydrate(parent(vec![
+            leaf("a", 1),
+            Other.any_view().with_key(Key(2)),
+        ]));
+        let after = nodes(&tree);
+        assert_eq!(after[0], before[0]);
+        assert_ne!(after[1], before[1]);
+        assert_eq!(after[1].view_type(), TypeId::of::<Other>());
+    }
+
+    #[derive(Debug, Clone, Hydrate)]
+    struct Button {
+        label: String,
+        clicks: State<u32>,
+        #[hydrate(ignore_eq)]
+        on_click: fn() -> u32,
+    }
+
+    impl View for Button {
+        fn children(&self) -> Vec<AnyView> {
+            Vec::new()
+        }
+    }
+
+    #[test]
+    fn skipped_hydration_refreshes_ignored_props() {
+        let button = |on_click| Button {
+            label: "ok".to_string(),
+            clicks: State::new(0),
+            on_click,
+        };
+        let mut tree = ViewTree::new(button(|| 1).any_view());
+        {
+            let button = tree.view.downcast_ref::<Button>().unwrap();
+            button.clicks.set(5);
+            button.clicks.clean();
+        }
+        tree.perform_hydrate(button(|| 2).any_view());
+        assert_eq!(tree.view.profile().skipped, 1);
+        let button = tree.view.downcast_ref::<Button>().unwrap();
+        assert_eq!((button.on_click)(), 2);
+        assert_eq!(button.clicks.get(), 5);
+    }
+
+    #[test]
+    fn measuring_keeps_the_last_layout() {
+        let stack = Stack::new()
+            .with_child(Text::new("hello"))
+            .with_child(Text::new("hi"));
+        let tree = ViewTree::new(stack.any_view());
+        tree.layout(Constraint::new(3.0, 10.0));
+        let child = &tree.children[0].view;
+        assert!(child.overflows());
+        assert_eq!(tree.max_intrinsic_width(10.0), 5.0);
+        assert_eq!(tree.max_intrinsic_height(3.0), 2.0);
+        assert_eq!(child.constraint.get(), Constraint::new(3.0, 10.0));
+        assert_eq!(child.size.get(), Size::new(5.0, 1.0));
+        assert!(child.overflows());
+    }
+
+    #[test]
+    fn measuring_is_profiled_apart_from_layout() {
+        let tree = ViewTree::new(Stack::new().with_child(Text::new("hello")).any_view());
+        tree.layout(Constraint::new(10.0, 10.0));
+        tree.max_intrinsic_width(10.0);
+        tree.max_intrinsic_width(10.0);
+        let profile = tree.view.profile();
+        assert_eq!((profile.layouts, profile.measures), (1, 1));
+        let child = tree.children[0].view.profile();
+        assert_eq!((child.layouts, child.measures), (1, 1));
+    }
+}
@@ -0,0 +1,429 @@
+use crate::*;
+use unicode_width::UnicodeWidthStr;
+
+type DismissFn = Rc<dyn Fn()>;
+
+/// The last layout rectangle of a view wrapped in `Anchored`, used to place
+/// popups next to it. Keep it in the parent's state and clone it into both
+/// the `Anchored` view and the `Modal` placement.
+#[derive(Clone, Default)]
+pub struct Anchor(Rc<Cell<Rect>>);
+
+impl PartialEq for Anchor {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Debug for Anchor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Anchor").field(&self.0.get()).finish()
+    }
+}
+
+impl Anchor {
+    pub fn new() -> Self {
+        Anchor::default()
+    }
+
+    pub fn rect(&self) -> Rect {
+        self.0.get()
+    }
+}
+
+/// Records the offset and size of its child into an `Anchor`.
+#[derive(Debug, Clone, PartialEq, Hydrate)]
+pub struct Anchored {
+    pub anchor: Anchor,
+    pub child: AnyView,
+}
+
+impl Anchored {
+    pub fn new(anchor: Anchor, child: impl View) -> Self {
+        Anchored {
+            anchor,
+            child: child.any_view(),
+        }
+    }
+}
+
+impl View for Anchored {
+    fn body(&self) -> AnyView {
+        self.child.clone()
+    }
+
+    fn set_offset(&self, children: &[ViewTree], offset: Offset) {
+        for child in children {
+            child.set_offset(offset);
+            self.anchor.0.set(child.view.rect());
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Placement {
+    /// Centered over the whole overlay area.
+    Center,
+    /// Below the anchored view, or above it if there is no room below.
+    Below(Anchor),
+}
+
+/// Shows `layer` above `content`.
+///
+/// The layer is laid out against the full area given to the overlay, so the
+/// overlay is normally the root view. When the layer is a `Modal`, the run
+/// loop only delivers events to the modal and keeps the focus inside it.
+/// Layers are centered unless their `View::placement` says otherwise.
+#[derive(Debug, Clone)]
+pub struct Overlay {
+    pub content: AnyView,
+    pub layer: Option<AnyView>,
+    // The area the layer is placed in, from the last layout.
+    area: State<Size>,
+}
+
+impl Overlay {
+    pub fn new(content: impl View) -> Self {
+        Overlay {
+            content: content.any_view(),
+            layer: None,
+            area: State::new(Size::zero()),
+        }
+    }
+
+    /// Show `layer` above the content, or nothing when `None`.
+    pub fn layer(mut self, layer: Option<impl View>) -> Self {
+        self.layer = layer.map(|layer| layer.any_view());
+        self
+    }
+}
+
+impl Hydrate for Overlay {
+    fn hydrate(&mut self, other: &Self) {
+        self.content = other.content.clone();
+        self.layer = other.layer.clone();
+    }
+}
+
+impl View for Overlay {
+    fn children(&self) -> Vec<AnyView> {
+        let mut children = vec![self.content.clone()];
+        children.extend(self.layer.clone());
+        children
+    }
+
+    fn layout(&self, children: &[ViewTree], constraint: Constraint) -> Size {
+        let area = constraint.max_size();
+        if area != self.area.get() {
+            self.area.set(area);
+        }
+        let mut size = Size::zero();
+        if let Some((content, layers)) = children.split_first() {
+            content.layout(constraint);
+            size = content.view.size.get();
+            for layer in layers {
+                layer.layout(Constraint::loose(area));
+            }
+        }
+        size
+    }
+
+    // Layers float above the content and don't add to its size.
+    fn intrinsic(&self, children: &[ViewTree], kind: Intrinsic, extent: f64) -> f64 {
+        children
+            .first()
+            .map_or(0.0, |content| content.intrinsic(kind, extent))
+    }
+
+    // Layers are placed in the whole area, which can be larger than the
+    // content.
+    fn clip_children(&self, _bounds: Rect) -> Option<Rect> {
+        None
+    }
+
+    fn set_offset(&self, children: &[ViewTree], offset: Offset) {
+        let Some((content, layers)) = children.split_first() else {
+            return;
+        };
+        content.set_offset(offset);
+        let area = self.area.get();
+        for layer in layers {
+            let size = layer.view.size.get();
+            let placement = layer.view.borrow().placement();
+            let at = match placement {
+                Placement::Center => {
+                    let at = Alignment::CENTER.position(size, area);
+                    offset.translate(at.x, at.y)
+                }
+                Placement::Below(anchor) => {
+                    let anchor = anchor.rect();
+                    let y = if anchor.bottom() + size.height <= offset.y + area.height {
+                        anchor.bottom()
+                    } else {
+                        anchor.top() - size.height
+                    };
+                    Offset::new(anchor.left(), y)
+                }
+            };
+            // Keep the layer inside the area.
+            layer.set_offset(Offset {
+                x: at.x.min(offset.x + area.width - size.width).max(offset.x),
+                y: at.y.min(offset.y + area.height - size.height).max(offset.y),
+            });
+        }
+    }
+}
+
+/// A framed layer that captures input while it is shown.
+///
+/// Escape and, with `dismiss_on_outside_click`, clicks outside the frame call
+/// `on_dismiss`; the parent is expected to stop showing the modal then.
+/// Other clicks outside the frame are blocked.
+#[derive(Clone)]
+pub struct Modal {
+    pub child: AnyView,
+    pub title: Option<String>,
+    pub placement: Placement,
+    pub on_dismiss: Option<DismissFn>,
+    pub dismiss_on_outside_click: bool,
+}
+
+impl Debug for Modal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Modal")
+            .field("title", &self.title)
+            .field("placement", &self.placement)
+            .field("dismiss_on_outside_click", &self.dismiss_on_outside_click)
+            .finish()
+    }
+}
+
+impl Modal {
+    pub fn new(child: impl View) -> Self {
+        Modal {
+            child: child.any_view(),
+            title: None,
+            placement: Placement::Center,
+            on_dismiss: None,
+            dismiss_on_outside_click: false,
+        }
+    }
+
+    pub fn title(mut self, title: impl ToString) -> Self {
+        self.title = Some(title.to_string());
+        self
+    }
+
+    pub fn below(mut self, anchor: Anchor) -> Self {
+        self.placement = Placement::Below(anchor);
+        self
+    }
+
+    pub fn on_dismiss(mut self, cb: impl Fn() + 'static) -> Self {
+        self.on_dismiss = Some(Rc::new(cb));
+        self
+    }
+
+    pub fn dismiss_on_outside_click(mut self) -> Self {
+        self.dismiss_on_outside_click = true;
+        self
+    }
+
+    // The title with a space on each side.
+    fn title_width(&self) -> f64 {
+        self.title.as_ref().map_or(0, |title| title.width() + 2) as f64
+    }
+
+    fn dismiss(&self) -> bool {
+        match &self.on_dismiss {
+            Some(cb) => {
+                cb();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl Hydrate for Modal {
+    fn hydrate(&mut self, other: &Self) {
+        self.child = other.child.clone();
+        self.title = other.title.clone();
+        self.placement = other.placement.clone();
+        self.on_dismiss = other.on_dismiss.clone();
+        self.dismiss_on_outside_click = other.dismiss_on_outside_click;
+    }
+}
+
+impl View for Modal {
+    fn body(&self) -> AnyView {
+        self.child.clone()
+    }
+
+    fn is_modal(&self) -> bool {
+        true
+    }
+
+    fn placement(&self) -> Placement {
+        self.placement.clone()
+    }
+
+    // One cell of frame on every side.
+    fn layout(&self, children: &[ViewTree], constraint: Constraint) -> Size {
+        let frame = EdgeInsets::all(1.0);
+        let mut size = Size::zero();
+        for child in children {
+            child.layout(constraint.deflate(frame));
+            size = child.view.size.get();
+        }
+        frame.inflate(Size::new(size.width.max(self.title_width()), size.height))
+    }
+
+    fn intrinsic(&self, children: &[ViewTree], kind: Intrinsic, extent: f64) -> f64 {
+        let child = children
+            .first()
+            .map_or(0.0, |child| child.intrinsic(kind, (extent - 2.0).max(0.0)));
+        if kind.is_width() {
+            child.max(self.title_width()) + 2.0
+        } else {
+            child + 2.0
+        }
+    }
+
+    fn set_offset(&self, children: &[ViewTree], offset: Offset) {
+        for child in children {
+            child.set_offset(offset.translate(1.0, 1.0));
+        }
+    }
+
+    fn event(&self, size: Size, offset: Offset, _children: &[ViewTree], event: &Event) -> bool {
+        match *event {
+            Event::MousePress(_, x, y) => {
+                let inside = Rect::new(offset, size).contains(x, y);
+                if !inside && self.dismiss_on_outside_click {
+                    self.dismiss();
+                }
+                !inside
+            }
+            Event::KeyPress(KeyCode::Esc, _) => self.dismiss(),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq, Hydrate)]
+    struct Field {
+        name: &'static str,
+    }
+
+    impl View for Field {
+        fn children(&self) -> Vec<AnyView> {
+            Vec::new()
+        }
+
+        fn layout(&self, _children: &[ViewTree], _constraint: Constraint) -> Size {
+            Size::new(4.0, 1.0)
+        }
+
+        fn focusable(&self) -> bool {
+            true
+        }
+    }
+
+    fn name(view: Option<&AnyView>) -> Option<&'static str> {
+        view.map(|view| view.downcast_ref::<Field>().unwrap().name)
+    }
+
+    fn overlay(modal: bool) -> AnyView {
+        let fields = Modal::new(
+            Stack::new()
+                .with_child(Field { name: "ok" })
+                .with_child(Field { name: "cancel" }),
+        );
+        Overlay::new(Field { name: "behind" })
+            .layer(modal.then_some(fields))
+            .any_view()
+    }
+
+    #[test]
+    fn focus_stays_in_the_modal() {
+        let mut tree = ViewTree::new(overlay(false));
+        let mut focus = Focus::new();
+        focus.next(&tree);
+        assert_eq!(name(focus.current()), Some("behind"));
+
+        tree.perform_hydrate(overlay(true));
+        focus.trap(&tree);
+        assert_eq!(name(focus.current()), Some("ok"));
+        let modal = tree.modal().unwrap();
+        focus.next(modal);
+        assert_eq!(name(focus.current()), Some("cancel"));
+        focus.next(modal);
+        assert_eq!(name(focus.current()), Some("ok"));
+
+        // The focus goes back once the modal is gone.
+        tree.perform_hydrate(overlay(false));
+        focus.sync(&tree);
+        focus.trap(&tree);
+        assert_eq!(name(focus.current()), Some("behind"));
+    }
+
+    #[test]
+    fn modal_is_centered_in_the_area() {
+        let tree = ViewTree::new(overlay(true));
+        tree.layout(Constraint::new(20.0, 10.0));
+        tree.set_offset(Offset::zero());
+        let modal = tree.modal().unwrap();
+        assert_eq!(
+            modal.view.rect(),
+            Rect::new(Offset::new(7.0, 3.0), Size::new(6.0, 4.0))
+        );
+    }
+
+    fn modal(dismissed: &Rc<Cell<u32>>) -> Modal {
+        let dismissed = dismissed.clone();
+        Modal::new(Field { name: "ok" }).on_dismiss(move || dismissed.set(dismissed.get() + 1))
+    }
+
+    fn press(modal: &Modal, x: f64, y: f64) -> bool {
+        let size = Size::new(6.0, 3.0);
+        let event = Event::MousePress(MouseButton::Left, x, y);
+        modal.event(size, Offset::new(10.0, 10.0), &[], &event)
+    }
+
+    #[test]
+    fn outside_clicks_are_blocked() {
+        let dismissed = Rc::new(Cell::new(0));
+        let modal = modal(&dismissed);
+        assert!(press(&modal, 0.0, 0.0));
+        assert_eq!(dismissed.get(), 0);
+        // Clicks inside reach the content.
+        assert!(!press(&modal, 11.0, 11.0));
+    }
+
+    #[test]
+    fn outside_clicks_can_dismiss() {
+        let dismissed = Rc::new(Cell::new(0));
+        let modal = modal(&dismissed).dismiss_on_outside_click();
+        assert!(press(&modal, 0.0, 0.0));
+        assert_eq!(dismissed.get(), 1);
+        assert!(!press(&modal, 11.0, 11.0));
+        assert_eq!(dismissed.get(), 1);
+    }
+
+    #[test]
+    fn escape_dismisses() {
+        let dismissed = Rc::new(Cell::new(0));
+        let escape = Event::KeyPress(KeyCode::Esc, Default::default());
+        let size = Size::new(6.0, 3.0);
+        assert!(modal(&dismissed).event(size, Offset::zero(), &[], &escape));
+        assert_eq!(dismissed.get(), 1);
+        // Without `on_dismiss`, Escape is left to the others.
+        let modal = Modal::new(Field { name: "ok" });
+        assert!(!modal.event(size, Offset::zero(), &[], &escape));
+    }
+}
@@ -0,0 +1,127 @@
+use crate::*;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// Counters and timings of a view, kept by `ViewTree` since the view
+/// entered the tree.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Profile {
+    /// Evaluations of `View::children`, which builds the body by default.
+    pub bodies: u32,
+    /// Hydrations that updated the view and rebuilt its body.
+    pub hydrations: u32,
+    /// Hydrations skipped because the view was clean and unchanged.
+    pub skipped: u32,
+    pub layouts: u32,
+    /// Intrinsic sizes computed, not counting the cached ones.
+    pub measures: u32,
+    pub body_time: Duration,
+    pub hydrate_time: Duration,
+    /// Time in `View::layout`, without the layouts of the children and the
+    /// measurements it asked for.
+    pub layout_time: Duration,
+    /// Time in `View::intrinsic`, without the children.
+    pub measure_time: Duration,
+}
+
+impl Profile {
+    pub fn total_time(&self) -> Duration {
+        self.body_time + self.hydrate_time + self.layout_time + self.measure_time
+    }
+}
+
+thread_local! {
+    // Time spent in the layouts and measurements nested in the current one,
+    // so a view can be timed without its children.
+    static NESTED_LAYOUT: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+}
+
+/// Run `f` and return how long it took.
+pub(crate) fn timed<R>(f: impl FnOnce() -> R) -> (R, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+/// Run a layout or a measurement and return how long it took, less the
+/// layouts and measurements nested in it.
+pub(crate) fn timed_layout<R>(layout: impl FnOnce() -> R) -> (R, Duration) {
+    let outer = NESTED_LAYOUT.with(|nested| nested.replace(Duration::ZERO));
+    let (result, elapsed) = timed(layout);
+    let nested = NESTED_LAYOUT.with(|nested| nested.replace(outer + elapsed));
+    (result, elapsed.saturating_sub(nested))
+}
+
+/// The profiles of the views of a tree, costliest first.
+#[derive(Debug, Clone)]
+pub struct ProfileReport {
+    pub entries: Vec<(AnyView, Profile)>,
+}
+
+impl ProfileReport {
+    pub fn new(tree: &ViewTree) -> ProfileReport {
+        let mut entries: Vec<_> = tree
+            .flatten()
+            .into_iter()
+            .map(|view| {
+                let profile = view.profile();
+                (view, profile)
+            })
+            .collect();
+        entries.sort_by_key(|(_, profile)| std::cmp::Reverse(profile.total_time()));
+        ProfileReport { entries }
+    }
+}
+
+impl fmt::Display for ProfileReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:>10} {:>10} {:>10} {:>10} {:>10} {:>7} {:>7} {:>7} {:>7} {:>7}  view",
+            "total ms",
+            "body ms",
+            "hydrate ms",
+            "layout ms",
+            "measure ms",
+            "bodies",
+            "hydr.",
+            "skipped",
+            "layouts",
+            "measures"
+        )?;
+        let ms = |time: Duration| time.as_secs_f64() * 1000.0;
+        for (view, profile) in &self.entries {
+            writeln!(
+                f,
+                "{:>10.3} {:>10.3} {:>10.3} {:>10.3} {:>10.3} {:>7} {:>7} {:>7} {:>7} {:>7}  {} {:?}",
+                ms(profile.total_time()),
+                ms(profile.body_time),
+                ms(profile.hydrate_time),
+                ms(profile.layout_time),
+                ms(profile.measure_time),
+                profile.bodies,
+                profile.hydrations,
+                profile.skipped,
+                profile.layouts,
+                profile.measures,
+                view.short_type_name(),
+                view.key()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl ViewTree {
+    /// The profiles of every view in the tree, costliest first.
+    pub fn profile_report(&self) -> ProfileReport {
+        ProfileReport::new(self)
+    }
+
+    /// Start counting afresh for every view in the tree.
+    pub fn reset_profile(&self) {
+        for view in self.flatten() {
+            view.profile.set(Profile::default());
+        }
+    }
+}
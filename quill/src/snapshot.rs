@@ -0,0 +1,65 @@
+use crate::*;
+
+/// A copy of a `ViewTree` as plain data, for tools and for comparing frames
+/// in tests. Serializes with serde, e.g. to JSON with `to_json`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// The full type name of the view, see `AnyView::type_name`.
+    #[serde(rename = "type")]
+    pub type_name: String,
+    pub key: Key,
+    pub size: Size,
+    pub offset: Offset,
+    pub dirty: bool,
+    pub children: Vec<Snapshot>,
+}
+
+impl Snapshot {
+    pub fn new(tree: &ViewTree) -> Snapshot {
+        Snapshot {
+            type_name: tree.view.type_name().to_string(),
+            key: tree.view.key(),
+            size: tree.view.size.get(),
+            offset: tree.view.offset.get(),
+            dirty: tree.view.borrow().is_dirty(),
+            children: tree.children.iter().map(Snapshot::new).collect(),
+        }
+    }
+
+    /// Indented JSON. Infinite and NaN sizes and offsets are written as the
+    /// strings `"inf"`, `"-inf"` and `"NaN"`, so they read back unchanged.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("a snapshot is always valid JSON")
+    }
+
+    pub fn from_json(json: &str) -> serde_json::Result<Snapshot> {
+        serde_json::from_str(json)
+    }
+}
+
+impl ViewTree {
+    /// The tree as it is now, from the last hydration and layout.
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_round_trip_keeps_infinite_sizes() {
+        let tree = ViewTree::new(Stack::new().with_child(Text::new("hi")).any_view());
+        tree.layout(Constraint::new(10.0, 5.0));
+        tree.children[0]
+            .view
+            .size
+            .set(Size::new(f64::INFINITY, 1.0));
+        tree.set_offset(Offset::new(f64::NEG_INFINITY, 2.5));
+        let snapshot = tree.snapshot();
+        let json = snapshot.to_json();
+        assert!(json.contains("\"inf\"") && json.contains("\"-inf\""));
+        assert_eq!(Snapshot::from_json(&json).unwrap(), snapshot);
+    }
+}
@@ -0,0 +1,243 @@
+use crate::*;
+
+type RowFn = Rc<dyn Fn(usize) -> AnyView>;
+
+/// A vertical list that only builds the rows intersecting its viewport.
+///
+/// Rows are produced on demand by `row` and keyed by their slot in the
+/// viewport rather than by their index, so scrolling hydrates the existing
+/// nodes with the rows that scrolled into view instead of building new ones.
+/// The number of nodes therefore depends on the viewport height only, not on
+/// `count`.
+#[derive(Clone)]
+pub struct VirtualList {
+    pub count: usize,
+    pub row_height: f64,
+    pub row: RowFn,
+    /// Index of the first visible row, when bound by the parent.
+    pub scroll: Option<State<usize>>,
+    pub height: Option<f64>,
+    // Index of the first visible row when `scroll` isn't bound.
+    offset: State<usize>,
+    // Height from the last layout, or `height` until the first one. Children
+    // are built before layout, so a change asks for a rebuild.
+    viewport: State<f64>,
+}
+
+impl Debug for VirtualList {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VirtualList")
+            .field("count", &self.count)
+            .field("row_height", &self.row_height)
+            .field("scroll", self.scroll_state())
+            .field("viewport", &self.viewport)
+            .finish()
+    }
+}
+
+impl VirtualList {
+    pub fn new(count: usize, row_height: f64, row: impl Fn(usize) -> AnyView + 'static) -> Self {
+        VirtualList {
+            count,
+            row_height: row_height.max(1.0),
+            row: Rc::new(row),
+            scroll: None,
+            height: None,
+            offset: State::new(0),
+            viewport: State::new(0.0),
+        }
+    }
+
+    /// Bind the scroll position to a state owned by the parent.
+    pub fn scroll(mut self, scroll: State<usize>) -> Self {
+        self.scroll = Some(scroll);
+        self
+    }
+
+    pub fn height(mut self, height: f64) -> Self {
+        self.height = Some(height);
+        self.viewport.set(height);
+        self
+    }
+
+    fn scroll_state(&self) -> &State<usize> {
+        self.scroll.as_ref().unwrap_or(&self.offset)
+    }
+
+    /// Rows per viewport, counting a partially visible last row. All of them
+    /// in an unbounded viewport.
+    pub fn page(&self) -> usize {
+        let viewport = self.viewport.get();
+        if viewport.is_finite() {
+            (viewport / self.row_height).ceil() as usize
+        } else {
+            self.count
+        }
+    }
+
+    /// The range of rows currently built.
+    pub fn visible(&self) -> std::ops::Range<usize> {
+        let first = self.first();
+        first..first.saturating_add(self.page()).min(self.count)
+    }
+
+    fn first(&self) -> usize {
+        self.scroll_state()
+            .get()
+            .min(self.count.saturating_sub(self.page()))
+    }
+
+    pub fn scroll_by(&self, delta: isize) {
+        let max = self.count.saturating_sub(self.page()) as isize;
+        let to = (self.first() as isize + delta).clamp(0, max.max(0)) as usize;
+        let scroll = self.scroll_state();
+        if to != scroll.get() {
+            scroll.set(to);
+        }
+    }
+}
+
+impl Hydrate for VirtualList {
+    fn hydrate(&mut self, other: &Self) {
+        self.count = other.count;
+        self.row_height = other.row_height;
+        self.row = other.row.clone();
+        // `offset` is kept, so an unbound scroll keeps its position.
+        self.scroll = other.scroll.clone();
+        self.height = other.height;
+        if let Some(height) = other.height {
+            self.viewport.set(height);
+        }
+    }
+}
+
+impl View for VirtualList {
+    fn children(&self) -> Vec<AnyView> {
+        let slots = self.page().max(1);
+        self.visible()
+            .map(|nth| (self.row)(nth).with_key(Key((nth % slots) as u32)))
+            .collect()
+    }
+
+    fn layout(&self, children: &[ViewTree], constraint: Constraint) -> Size {
+        // All the rows in unbounded space.
+        let height = self.height.unwrap_or_else(|| {
+            bounded(constraint.max_height(), self.count as f64 * self.row_height)
+        });
+        if height != self.viewport.get() {
+            self.viewport.set(height);
+            // The rows were built for the old height.
+            request_rebuild();
+        }
+        let row_constraint = Constraint::between(
+            Size::new(constraint.min_width(), 0.0),
+            Size::new(constraint.max_width(), self.row_height),
+        );
+        let mut width: f64 = 0.0;
+        for child in children {
+            child.layout(row_constraint);
+            width = width.max(child.view.size.get().width);
+        }
+        Size { width, height }
+    }
+
+    // As wide as the rows built so far, as tall as all of them.
+    fn intrinsic(&self, children: &[ViewTree], kind: Intrinsic, _extent: f64) -> f64 {
+        if kind.is_width() {
+            children
+                .iter()
+                .map(|child| child.intrinsic(kind, self.row_height))
+                .fold(0.0, f64::max)
+        } else {
+            self.height.unwrap_or(self.count as f64 * self.row_height)
+        }
+    }
+
+    fn set_offset(&self, children: &[ViewTree], offset: Offset) {
+        for (line, child) in children.iter().enumerate() {
+            child.set_offset(Offset {
+                x: offset.x,
+                y: offset.y + line as f64 * self.row_height,
+            });
+        }
+    }
+
+    fn event(&self, size: Size, offset: Offset, _children: &[ViewTree], event: &Event) -> bool {
+        match *event {
+            Event::MousePress(MouseButton::WheelUp, x, y)
+            | Event::MousePress(MouseButton::WheelDown, x, y)
+                if Rect::new(offset, size).contains(x, y) =>
+            {
+                let up = matches!(event, Event::MousePress(MouseButton::WheelUp, ..));
+                self.scroll_by(if up { -3 } else { 3 });
+                true
+            }
+            // Keys bubbling up from a focused row.
+            Event::KeyPress(KeyCode::PageUp, mods) if !mods.ctrl => {
+                self.scroll_by(-(self.page() as isize));
+                true
+            }
+            Event::KeyPress(KeyCode::PageDown, mods) if !mods.ctrl => {
+                self.scroll_by(self.page() as isize);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(count: usize) -> VirtualList {
+        VirtualList::new(count, 1.0, |nth| Text::new(nth).any_view())
+    }
+
+    #[test]
+    fn first_layout_rebuilds_the_rows() {
+        let mut tree = ViewTree::new(rows(100).any_view());
+        assert!(tree.children.is_empty());
+        take_rebuild();
+        tree.layout(Constraint::new(20.0, 5.0));
+        assert!(take_rebuild());
+        tree.perform_hydrate(rows(100).any_view());
+        assert_eq!(tree.children.len(), 5);
+        tree.layout(Constraint::new(20.0, 5.0));
+        assert!(!take_rebuild());
+    }
+
+    #[test]
+    fn partial_rows_round_the_same_way() {
+        let list = rows(10).height(5.5);
+        list.offset.set(100);
+        assert_eq!(list.page(), 6);
+        assert_eq!(list.visible(), 4..10);
+        list.scroll_by(-1);
+        assert_eq!(list.visible(), 3..9);
+    }
+
+    #[test]
+    fn unbounded_height_builds_every_row() {
+        let mut tree = ViewTree::new(rows(3).any_view());
+        tree.layout(Constraint::unbounded());
+        assert_eq!(tree.view.size.get(), Size::new(0.0, 3.0));
+        take_rebuild();
+        tree.perform_hydrate(rows(3).any_view());
+        assert_eq!(tree.children.len(), 3);
+    }
+
+    #[test]
+    fn unbound_scroll_survives_hydration() {
+        let mut tree = ViewTree::new(rows(100).height(5.0).any_view());
+        let list = |tree: &ViewTree| tree.view.downcast_ref::<VirtualList>().unwrap().clone();
+        list(&tree).scroll_by(10);
+        tree.perform_hydrate(rows(100).height(5.0).any_view());
+        assert_eq!(list(&tree).visible(), 10..15);
+
+        // A bound scroll is taken from the new view.
+        let scroll = State::new(20);
+        tree.perform_hydrate(rows(100).height(5.0).scroll(scroll).any_view());
+        assert_eq!(list(&tree).visible(), 20..25);
+    }
+}